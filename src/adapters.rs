@@ -0,0 +1,297 @@
+use crate::translator::{Stream, Translator, CHUNK_SIZE};
+
+use std::io::{self, Read, Write};
+
+/// Wraps a reader and translates, deletes and squeezes whatever is read from it.
+/// Squeezing carries on from one call to `read` to the next.
+///
+/// ```
+/// use cctr::adapters::TrReader;
+/// use cctr::translator::Translator;
+/// use std::io::Read;
+///
+/// let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+/// let mut reader = TrReader::new("coding challenge".as_bytes(), translator);
+///
+/// let mut output = String::new();
+/// reader.read_to_string(&mut output).unwrap();
+///
+/// assert_eq!(output, "CODING CHALLENGE");
+/// ```
+#[derive(Debug)]
+pub struct TrReader<R: Read> {
+    inner: R,
+    translator: Translator,
+    stream: Stream,
+    /// A chunk of input read from the inner reader
+    chunk: Vec<u8>,
+    /// Processed output that has not been read yet
+    output: Vec<u8>,
+    /// How much of `output` has been read
+    pos: usize,
+    /// True once the inner reader has run out
+    done: bool,
+}
+
+impl<R: Read> TrReader<R> {
+    pub fn new(inner: R, translator: Translator) -> TrReader<R> {
+        TrReader {
+            inner,
+            translator,
+            stream: Stream::default(),
+            chunk: vec![0; CHUNK_SIZE],
+            output: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Returns a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Unwrap the inner reader. Any output that has not been read is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for TrReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Keep reading until there is some output, as a whole chunk of input
+        // may have been deleted
+        while self.pos == self.output.len() {
+            if self.done {
+                return Ok(0);
+            }
+
+            self.output.clear();
+            self.pos = 0;
+
+            let len = self.inner.read(&mut self.chunk)?;
+            if len == 0 {
+                self.stream.finish(&self.translator, &mut self.output)?;
+                self.done = true;
+            } else {
+                let chunk = &self.chunk[..len];
+                self.stream
+                    .write(&self.translator, chunk, &mut self.output)?;
+            }
+        }
+
+        let len = buf.len().min(self.output.len() - self.pos);
+        buf[..len].copy_from_slice(&self.output[self.pos..self.pos + len]);
+        self.pos += len;
+
+        Ok(len)
+    }
+}
+
+/// Wraps a writer and translates, deletes and squeezes whatever is written to
+/// it. Squeezing carries on from one call to `write` to the next.
+///
+/// A character can be split between two writes, so call `finish` once all the
+/// input has been written. Otherwise a character cut off at the very end is
+/// lost instead of being reported or passed through.
+///
+/// ```
+/// use cctr::adapters::TrWriter;
+/// use cctr::translator::Translator;
+/// use std::io::Write;
+///
+/// let translator = Translator::builder("\\n").squeeze(true).build().unwrap();
+/// let mut writer = TrWriter::new(Vec::new(), translator);
+///
+/// writer.write_all(b"coding\n\n").unwrap();
+/// writer.write_all(b"\nchallenge\n").unwrap();
+///
+/// assert_eq!(writer.finish().unwrap(), b"coding\nchallenge\n");
+/// ```
+#[derive(Debug)]
+pub struct TrWriter<W: Write> {
+    inner: W,
+    translator: Translator,
+    stream: Stream,
+}
+
+impl<W: Write> TrWriter<W> {
+    pub fn new(inner: W, translator: Translator) -> TrWriter<W> {
+        TrWriter {
+            inner,
+            translator,
+            stream: Stream::default(),
+        }
+    }
+
+    /// Returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Handle the end of the input, flush the inner writer and return it
+    pub fn finish(mut self) -> io::Result<W> {
+        self.stream.finish(&self.translator, &mut self.inner)?;
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for TrWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(&self.translator, buf, &mut self.inner)?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::InvalidUtf8;
+
+    /// A reader that hands out its input a few bytes at a time
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(3);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+
+            Ok(len)
+        }
+    }
+
+    // ************************************************************************
+    // reader tests
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_while_reading() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let mut reader = TrReader::new(Trickle(b"coding\nchallenge\n"), translator);
+
+        let mut output = String::new();
+        let result = reader.read_to_string(&mut output);
+
+        assert!(result.is_ok());
+        assert_eq!(output, "CODING\nCHALLENGE\n");
+    }
+
+    #[test]
+    fn squeezing_carries_on_across_reads() {
+        let translator = Translator::builder("o").squeeze(true).build().unwrap();
+        let mut reader = TrReader::new(Trickle(b"coooooding"), translator);
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+
+        assert_eq!(output, "coding");
+    }
+
+    #[test]
+    fn can_read_into_a_small_buffer() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let mut reader = TrReader::new("coding".as_bytes(), translator);
+
+        let mut buf = [0; 4];
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert_eq!(&buf, b"CODI");
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], b"NG");
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn can_read_when_a_whole_chunk_is_deleted() {
+        let translator = Translator::builder("0-9").delete(true).build().unwrap();
+        let mut reader = TrReader::new(Trickle(b"123456coding"), translator);
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+
+        assert_eq!(output, "coding");
+    }
+
+    #[test]
+    fn reading_invalid_utf8_returns_error() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let mut reader = TrReader::new(Trickle(b"coding\xff"), translator);
+
+        let mut output = Vec::new();
+        let result = reader.read_to_end(&mut output);
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // ************************************************************************
+    // writer tests
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_while_writing() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let mut writer = TrWriter::new(Vec::new(), translator);
+
+        writer.write_all(b"coding ").unwrap();
+        writer.write_all(b"challenge").unwrap();
+
+        assert_eq!(writer.finish().unwrap(), b"CODING CHALLENGE");
+    }
+
+    #[test]
+    fn squeezing_carries_on_across_writes() {
+        let translator = Translator::builder("l")
+            .string2("l")
+            .squeeze(true)
+            .build()
+            .unwrap();
+        let mut writer = TrWriter::new(Vec::new(), translator);
+
+        writer.write_all(b"chal").unwrap();
+        writer.write_all(b"llenge").unwrap();
+
+        assert_eq!(writer.finish().unwrap(), b"chalenge");
+    }
+
+    #[test]
+    fn characters_can_be_split_across_writes() {
+        let translator = Translator::builder("[=e=]").string2("e").build().unwrap();
+        let mut writer = TrWriter::new(Vec::new(), translator);
+
+        writer.write_all(b"caf\xc3").unwrap();
+        writer.write_all(b"\xa9").unwrap();
+
+        assert_eq!(writer.finish().unwrap(), b"cafe");
+    }
+
+    #[test]
+    fn finishing_with_a_character_cut_off_returns_error() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let mut writer = TrWriter::new(Vec::new(), translator);
+
+        writer.write_all(b"caf\xc3").unwrap();
+
+        assert!(writer.finish().is_err());
+    }
+
+    #[test]
+    fn can_pass_a_character_cut_off_through_when_finishing() {
+        let translator = Translator::builder("a-z")
+            .string2("A-Z")
+            .invalid_utf8(InvalidUtf8::Pass)
+            .build()
+            .unwrap();
+        let mut writer = TrWriter::new(Vec::new(), translator);
+
+        writer.write_all(b"caf\xc3").unwrap();
+
+        assert_eq!(writer.finish().unwrap(), b"CAF\xc3");
+    }
+}
@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use clap_verbosity_flag::Verbosity;
 
 /// Defines the arguments and flags the user can input into the CLI tool.
 #[derive(Debug, Parser, Default)]
@@ -11,11 +12,14 @@ use clap::Parser;
 )]
 pub struct Cli {
     /// Complement the set of characters in string1, that is “-C ab” includes
-    /// every character except for ‘a’ and ‘b’.
+    /// every character except for ‘a’ and ‘b’. The complement pairs with
+    /// string2 in code point order.
     #[arg(short = 'C')]
     pub complement1: bool,
 
-    /// Same as -C but complement the set of values in string1.
+    /// Same as -C but, following POSIX, the complement pairs with string2 in
+    /// collation sequence, where letters sort alphabetically with lowercase
+    /// before uppercase.
     #[arg(short)]
     pub complement2: bool,
 
@@ -30,12 +34,83 @@ pub struct Cli {
     #[arg(short)]
     pub squeeze: bool,
 
+    /// Also delete the combining marks that follow a deleted character, so
+    /// that deleting `e` from `é` written as `e` and an accent leaves nothing
+    /// behind.
+    #[arg(long)]
+    pub delete_marks: bool,
+
     /// Guarantee that any output is unbuffered.
     #[arg(short)]
     pub unbuffered: bool,
 
+    /// Treat the input as raw bytes instead of UTF-8 text. Sets hold byte
+    /// values from `\0` to `\377` and classes only match ASCII characters.
+    /// This is also turned on when the locale is `C` or `POSIX`.
+    #[arg(long)]
+    pub bytes: bool,
+
+    /// Treat extended grapheme clusters, such as an `e` followed by a
+    /// combining accent or a flag emoji, as single characters in the sets and
+    /// the input, so they are deleted, squeezed and translated as a whole.
+    #[arg(long)]
+    pub graphemes: bool,
+
+    /// Normalize the input, and the characters in the sets, into a Unicode
+    /// normalization form before matching, so that `é` matches whether it is
+    /// written as one character or as `e` and a combining accent.
+    #[arg(long, value_enum)]
+    pub normalize: Option<Normalization>,
+
+    /// Normalize the output into a Unicode normalization form.
+    #[arg(long, value_enum)]
+    pub normalize_output: Option<Normalization>,
+
+    /// Transliterate the input into ASCII before it is deleted, translated and
+    /// squeezed, so that `é` becomes `e`, `æ` becomes `ae` and `€` becomes
+    /// `EUR`. On its own only the characters in string1 are transliterated,
+    /// and string1 can be left out to transliterate every character.
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// What to do with the characters that have no ASCII spelling
+    #[arg(long, value_enum, default_value_t)]
+    pub ascii_fallback: AsciiFallback,
+
+    /// What to do with input that is not valid UTF-8 when not in byte mode.
+    #[arg(long, value_enum, default_value_t)]
+    pub invalid_utf8: InvalidUtf8,
+
+    /// How to change the case of characters. Full mappings can turn one
+    /// character into several, such as `ß` into `SS`, while simple mappings keep
+    /// the output the same length as the input.
+    #[arg(long, value_enum, default_value_t)]
+    pub case_mapping: CaseMapping,
+
+    /// The locale whose case rules to use, such as `tr_TR`. Turkish, Azeri and
+    /// Lithuanian have rules of their own. Defaults to the locale set by
+    /// `LC_ALL`, `LC_CTYPE` or `LANG`. Only ASCII letters have case in byte
+    /// mode, so a locale cannot be given along with it.
+    #[arg(long)]
+    pub locale: Option<String>,
+
+    /// How much to log to stderr. Errors are always shown, `-v` adds warnings
+    /// and `-vvv` shows the compiled sets and how many characters changed.
+    #[command(flatten)]
+    pub verbose: Verbosity,
+
     /// A set of characters to translate into the characters in `string2`
-    #[arg(value_parser = not_empty)]
+    #[arg(
+        value_parser = not_empty,
+        required = false,
+        required_unless_present = "ascii",
+        required_if_eq_any([("delete", "true"), ("squeeze", "true")]),
+        default_value_ifs([
+            ("delete", "true", None),
+            ("squeeze", "true", None),
+            ("ascii", "true", Some("[:rune:]")),
+        ])
+    )]
     pub string1: String,
 
     /// A set of characters to replace the characters in `string1`
@@ -43,6 +118,93 @@ pub struct Cli {
     pub string2: Option<String>,
 }
 
+/// Defines how to handle input that is not valid UTF-8
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum InvalidUtf8 {
+    /// Stop with an error that gives the byte offset of the invalid sequence
+    #[default]
+    Reject,
+    /// Copy invalid sequences to the output unchanged
+    Pass,
+}
+
+/// Defines what happens to a character that has no ASCII spelling when
+/// transliterating
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum AsciiFallback {
+    /// Replace the character with `?`
+    #[default]
+    Replace,
+    /// Delete the character
+    Drop,
+    /// Copy the character to the output unchanged
+    Keep,
+}
+
+/// Defines the Unicode normalization forms
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Normalization {
+    /// Canonical composition, where `e` and a combining acute accent become `é`
+    Nfc,
+    /// Canonical decomposition, where `é` becomes `e` and a combining acute
+    /// accent
+    Nfd,
+    /// Compatibility composition, which also turns characters such as `ﬁ` into
+    /// `fi`
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+/// Defines how the case of a character is changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum CaseMapping {
+    /// Use the full mappings, where one character can become several
+    #[default]
+    Full,
+    /// Only map a character to a single character
+    Simple,
+}
+
+/// Defines the languages whose case rules differ from the default ones
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CaseRules {
+    /// The rules that apply to most languages
+    #[default]
+    Default,
+    /// Turkish and Azeri, where the capital of `i` is `İ` and the lowercase of
+    /// `I` is the dotless `ı`
+    Turkic,
+    /// Lithuanian, where an `i` or `j` keeps its dot when it has an accent
+    /// above, and loses the combining dot above that stood in for it when
+    /// uppercased
+    Lithuanian,
+    /// The C and POSIX locales, where only ASCII letters have case
+    Ascii,
+}
+
+impl CaseRules {
+    /// Pick the case rules for a locale such as `tr_TR.UTF-8` or `lt`. Only the
+    /// `C` and `POSIX` locales themselves limit case to ASCII, a codeset such as
+    /// `C.UTF-8` keeps the default rules.
+    pub fn from_locale(locale: &str) -> CaseRules {
+        if matches!(locale, "C" | "POSIX") {
+            return CaseRules::Ascii;
+        }
+
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default();
+
+        match language.to_ascii_lowercase().as_str() {
+            "tr" | "az" => CaseRules::Turkic,
+            "lt" => CaseRules::Lithuanian,
+            _ => CaseRules::Default,
+        }
+    }
+}
+
 /// Make sure that the string arguments are not empty
 fn not_empty(arg: &str) -> Result<String, String> {
     let s: String = arg
@@ -0,0 +1,451 @@
+use crate::error::Error;
+use crate::translator::{Stream as State, Translator, CHUNK_SIZE};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Wraps an async reader and translates, deletes and squeezes whatever is read
+/// from it. Squeezing carries on from one read to the next.
+#[derive(Debug)]
+pub struct AsyncTrReader<R: AsyncRead + Unpin> {
+    inner: R,
+    translator: Translator,
+    state: State,
+    /// A chunk of input read from the inner reader
+    chunk: Vec<u8>,
+    /// Processed output that has not been read yet
+    output: Vec<u8>,
+    /// How much of `output` has been read
+    pos: usize,
+    /// True once the inner reader has run out
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncTrReader<R> {
+    pub fn new(inner: R, translator: Translator) -> AsyncTrReader<R> {
+        AsyncTrReader {
+            inner,
+            translator,
+            state: State::default(),
+            chunk: vec![0; CHUNK_SIZE],
+            output: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Unwrap the inner reader. Any output that has not been read is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncTrReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        // Keep reading until there is some output, as a whole chunk of input
+        // may have been deleted
+        while this.pos == this.output.len() {
+            if this.done {
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut chunk = ReadBuf::new(&mut this.chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+
+            this.output.clear();
+            this.pos = 0;
+
+            let chunk = chunk.filled();
+            if chunk.is_empty() {
+                this.state.finish(&this.translator, &mut this.output)?;
+                this.done = true;
+            } else {
+                this.state
+                    .write(&this.translator, chunk, &mut this.output)?;
+            }
+        }
+
+        let len = buf.remaining().min(this.output.len() - this.pos);
+        buf.put_slice(&this.output[this.pos..this.pos + len]);
+        this.pos += len;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Wraps an async writer and translates, deletes and squeezes whatever is
+/// written to it. Squeezing carries on from one write to the next.
+///
+/// Shutting the writer down handles the end of the input, so a character cut
+/// off at the very end is reported or passed through.
+#[derive(Debug)]
+pub struct AsyncTrWriter<W: AsyncWrite + Unpin> {
+    inner: W,
+    translator: Translator,
+    state: State,
+    /// Processed output that has not been written to the inner writer yet
+    output: Vec<u8>,
+    /// How much of `output` has been written
+    pos: usize,
+    /// An error writing output after its input was taken, held for the next
+    /// call
+    error: Option<io::Error>,
+    /// True once the end of the input has been handled
+    finished: bool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncTrWriter<W> {
+    pub fn new(inner: W, translator: Translator) -> AsyncTrWriter<W> {
+        AsyncTrWriter {
+            inner,
+            translator,
+            state: State::default(),
+            output: Vec::new(),
+            pos: 0,
+            error: None,
+            finished: false,
+        }
+    }
+
+    /// Unwrap the inner writer. Any output that has not been written is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Write out any processed output that is waiting, returning the error
+    /// held from an earlier write first
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(e) = self.error.take() {
+            return Poll::Ready(Err(e));
+        }
+
+        while self.pos < self.output.len() {
+            let len = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.output[self.pos..]))?;
+            if len == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pos += len;
+        }
+
+        self.output.clear();
+        self.pos = 0;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncTrWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        // Only take more input once the output of the last write is gone
+        ready!(this.poll_drain(cx))?;
+        this.state.write(&this.translator, buf, &mut this.output)?;
+
+        // The input has been taken, so an error writing its output is
+        // returned by the next call instead
+        if let Poll::Ready(Err(e)) = this.poll_drain(cx) {
+            this.error = Some(e);
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if !this.finished {
+            this.finished = true;
+            this.state.finish(&this.translator, &mut this.output)?;
+        }
+
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+/// Translates, deletes and squeezes a stream of bytes. Each item of the inner
+/// stream is processed as it arrives and squeezing carries on from one item to
+/// the next. Items that are deleted entirely are skipped.
+#[derive(Debug)]
+pub struct TrStream<S: Stream<Item = Bytes> + Unpin> {
+    inner: S,
+    translator: Translator,
+    state: State,
+    /// True once the inner stream has ended or an error has been returned
+    done: bool,
+}
+
+impl<S: Stream<Item = Bytes> + Unpin> TrStream<S> {
+    pub fn new(inner: S, translator: Translator) -> TrStream<S> {
+        TrStream {
+            inner,
+            translator,
+            state: State::default(),
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream<Item = Bytes> + Unpin> Stream for TrStream<S> {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.done {
+            let mut output = Vec::new();
+            let result = match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                Some(chunk) => this.state.write(&this.translator, &chunk, &mut output),
+                None => {
+                    this.done = true;
+                    this.state.finish(&this.translator, &mut output)
+                }
+            };
+
+            if let Err(e) = result {
+                this.done = true;
+                return Poll::Ready(Some(Err(e)));
+            }
+
+            if !output.is_empty() {
+                return Poll::Ready(Some(Ok(Bytes::from(output))));
+            }
+        }
+
+        Poll::Ready(None)
+    }
+}
+
+/// Adds `translate` to streams of bytes
+pub trait TranslateExt: Stream<Item = Bytes> + Unpin + Sized {
+    /// Run every item of the stream through a translator
+    fn translate(self, translator: Translator) -> TrStream<Self> {
+        TrStream::new(self, translator)
+    }
+}
+
+impl<S: Stream<Item = Bytes> + Unpin> TranslateExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+    use tokio_stream::StreamExt;
+
+    // ************************************************************************
+    // reader tests
+    // ************************************************************************
+
+    #[tokio::test]
+    async fn can_translate_while_reading() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let (mut client, server) = duplex(4);
+        let mut reader = AsyncTrReader::new(server, translator);
+
+        tokio::spawn(async move {
+            client.write_all(b"coding\nchallenge\n").await.unwrap();
+        });
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).await.unwrap();
+
+        assert_eq!(output, "CODING\nCHALLENGE\n");
+    }
+
+    #[tokio::test]
+    async fn squeezing_carries_on_across_async_reads() {
+        let translator = Translator::builder("o").squeeze(true).build().unwrap();
+        let (mut client, server) = duplex(2);
+        let mut reader = AsyncTrReader::new(server, translator);
+
+        tokio::spawn(async move {
+            client.write_all(b"coooooding").await.unwrap();
+        });
+
+        let mut output = String::new();
+        reader.read_to_string(&mut output).await.unwrap();
+
+        assert_eq!(output, "coding");
+    }
+
+    #[tokio::test]
+    async fn reading_invalid_utf8_returns_error() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let (mut client, server) = duplex(64);
+        let mut reader = AsyncTrReader::new(server, translator);
+
+        tokio::spawn(async move {
+            client.write_all(b"coding\xff").await.unwrap();
+        });
+
+        let mut output = Vec::new();
+        let result = reader.read_to_end(&mut output).await;
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // ************************************************************************
+    // writer tests
+    // ************************************************************************
+
+    #[tokio::test]
+    async fn can_translate_while_writing() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let (client, mut server) = duplex(4);
+        let mut writer = AsyncTrWriter::new(client, translator);
+
+        tokio::spawn(async move {
+            writer.write_all(b"coding ").await.unwrap();
+            writer.write_all(b"challenge").await.unwrap();
+            writer.shutdown().await.unwrap();
+        });
+
+        let mut output = String::new();
+        server.read_to_string(&mut output).await.unwrap();
+
+        assert_eq!(output, "CODING CHALLENGE");
+    }
+
+    #[tokio::test]
+    async fn squeezing_carries_on_across_async_writes() {
+        let translator = Translator::builder("\\n").squeeze(true).build().unwrap();
+        let (client, mut server) = duplex(64);
+        let mut writer = AsyncTrWriter::new(client, translator);
+
+        tokio::spawn(async move {
+            writer.write_all(b"coding\n\n").await.unwrap();
+            writer.write_all(b"\nchallenge\n").await.unwrap();
+            writer.shutdown().await.unwrap();
+        });
+
+        let mut output = String::new();
+        server.read_to_string(&mut output).await.unwrap();
+
+        assert_eq!(output, "coding\nchallenge\n");
+    }
+
+    #[tokio::test]
+    async fn shutting_down_with_a_character_cut_off_returns_error() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let (client, _server) = duplex(64);
+        let mut writer = AsyncTrWriter::new(client, translator);
+
+        writer.write_all(b"caf\xc3").await.unwrap();
+
+        assert!(writer.shutdown().await.is_err());
+    }
+
+    /// A writer whose every write fails
+    struct BrokenWriter;
+
+    impl AsyncWrite for BrokenWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_to_write_taken_input_returns_error_from_next_call() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let mut writer = AsyncTrWriter::new(BrokenWriter, translator);
+
+        writer.write_all(b"coding").await.unwrap();
+        let result = writer.flush().await;
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    // ************************************************************************
+    // stream tests
+    // ************************************************************************
+
+    #[tokio::test]
+    async fn can_translate_a_stream_of_bytes() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let chunks = tokio_stream::iter(vec![Bytes::from("coding "), Bytes::from("challenge")]);
+
+        let output: Vec<_> = chunks.translate(translator).collect().await;
+
+        assert_eq!(
+            output.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec![Bytes::from("CODING "), Bytes::from("CHALLENGE")]
+        );
+    }
+
+    #[tokio::test]
+    async fn deleted_items_are_skipped() {
+        let translator = Translator::builder("0-9").delete(true).build().unwrap();
+        let chunks = tokio_stream::iter(vec![
+            Bytes::from("coding"),
+            Bytes::from("123"),
+            Bytes::from("challenge"),
+        ]);
+
+        let output: Vec<_> = chunks.translate(translator).collect().await;
+
+        assert_eq!(output.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stream_characters_can_be_split_across_items() {
+        let translator = Translator::builder("[=e=]").string2("e").build().unwrap();
+        let chunks = tokio_stream::iter(vec![
+            Bytes::from(&b"caf\xc3"[..]),
+            Bytes::from(&b"\xa9"[..]),
+        ]);
+
+        let output: Vec<_> = chunks.translate(translator).collect().await;
+
+        assert_eq!(
+            output.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec![Bytes::from("caf"), Bytes::from("e")]
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_of_invalid_utf8_returns_error() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+        let chunks = tokio_stream::iter(vec![Bytes::from(&b"ab\xff"[..])]);
+
+        let output: Vec<_> = chunks.translate(translator).collect().await;
+
+        assert!(matches!(
+            output.last(),
+            Some(Err(Error::InvalidUtf8 { offset: 2 }))
+        ));
+    }
+}
@@ -0,0 +1,408 @@
+use crate::set::{Pattern, CLASSES};
+
+use std::fmt;
+use std::io;
+use std::ops::Range;
+
+/// Defines the errors returned when building a `Translator` or running input
+/// through one.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A class written as `[:name:]` that does not exist, with the name of the
+    /// closest class if it looks like a typo
+    UnknownClass {
+        operand: Operand,
+        name: String,
+        offset: usize,
+        suggestion: Option<String>,
+    },
+    /// A class, equivalence class or repeat, as written in the set, that is
+    /// missing its closing brackets
+    Unterminated {
+        operand: Operand,
+        text: String,
+        offset: usize,
+        suggestion: String,
+    },
+    /// An equivalence class, as written in the set, that holds more than one
+    /// character
+    InvalidEquivalence {
+        operand: Operand,
+        equivalence: String,
+        offset: usize,
+        suggestion: String,
+    },
+    /// A range, as written in the set, whose end comes before its start
+    InvalidRange {
+        operand: Operand,
+        range: String,
+        offset: usize,
+    },
+    /// An escape sequence, as written in the set, that cannot be resolved
+    BadEscape {
+        operand: Operand,
+        sequence: String,
+        offset: usize,
+    },
+    /// A repeat such as `[a*x]` whose count is not a number, with a repeat that
+    /// can be read if the count holds any digits
+    InvalidRepeat {
+        operand: Operand,
+        repeat: String,
+        offset: usize,
+        suggestion: Option<String>,
+    },
+    /// A repeat in string1, or a second `[c*]` repeat in string2
+    MisplacedRepeat {
+        operand: Operand,
+        repeat: String,
+        offset: usize,
+    },
+    /// A character above `\377` in byte mode
+    NotAByte {
+        operand: Operand,
+        character: String,
+        offset: usize,
+    },
+    /// A character, as written in the set, that its normalization form turns
+    /// into more than one character, such as `ﬁ` under NFKC
+    SplitByNormalization {
+        operand: Operand,
+        character: String,
+        normalized: String,
+        offset: usize,
+    },
+    /// A part of string1 that cannot be translated into the class in string2 it is
+    /// paired with
+    Untranslatable { from: Pattern, to: Pattern },
+    /// string2 is empty, so there is nothing to translate into
+    EmptyString2,
+    /// The options need string2 but none was given
+    MissingString2,
+    /// string2 was given along with options that cannot use it
+    IncompatibleFlags,
+    /// The input held a sequence that is not UTF-8, starting this many bytes in
+    InvalidUtf8 { offset: usize },
+    /// Reading the input or writing the output failed
+    Io(io::Error),
+}
+
+/// Defines which of the two set strings an error was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    String1,
+    String2,
+}
+
+impl Error {
+    /// Returns the set string the error was found in and the bytes of it that
+    /// are at fault, for errors that come from a part of a set string
+    pub fn span(&self) -> Option<(Operand, Range<usize>)> {
+        let (operand, offset, len) = match self {
+            Error::UnknownClass {
+                operand,
+                name,
+                offset,
+                ..
+            } => (operand, offset, name.len() + 4),
+            Error::InvalidRange {
+                operand,
+                range: text,
+                offset,
+            }
+            | Error::BadEscape {
+                operand,
+                sequence: text,
+                offset,
+            }
+            | Error::Unterminated {
+                operand,
+                text,
+                offset,
+                ..
+            }
+            | Error::InvalidEquivalence {
+                operand,
+                equivalence: text,
+                offset,
+                ..
+            }
+            | Error::InvalidRepeat {
+                operand,
+                repeat: text,
+                offset,
+                ..
+            }
+            | Error::MisplacedRepeat {
+                operand,
+                repeat: text,
+                offset,
+            }
+            | Error::NotAByte {
+                operand,
+                character: text,
+                offset,
+            }
+            | Error::SplitByNormalization {
+                operand,
+                character: text,
+                offset,
+                ..
+            } => (operand, offset, text.len()),
+            _ => return None,
+        };
+
+        Some((*operand, *offset..*offset + len))
+    }
+
+    /// Render the error followed by the set string it was found in, with a
+    /// caret under the part of the set that is at fault. An unknown class also
+    /// lists the classes that can be used:
+    ///
+    /// ```text
+    /// Unknown class `[:alhpa:]` in string1. Did you mean `[:alpha:]`?
+    ///   a[:alhpa:]
+    ///    ^^^^^^^^^
+    /// Supported classes: [:alnum:] [:alpha:] [:blank:] ...
+    /// ```
+    pub fn render(&self, string1: &str, string2: Option<&str>) -> String {
+        let (set, span) = match self.span() {
+            Some((Operand::String1, span)) => (string1, span),
+            Some((Operand::String2, span)) => (string2.unwrap_or_default(), span),
+            None => return self.to_string(),
+        };
+
+        let mut rendered = match (set.get(..span.start), set.get(span.clone())) {
+            (Some(before), Some(part)) => format!(
+                "{}\n  {}\n  {}{}",
+                self,
+                set,
+                " ".repeat(before.chars().count()),
+                "^".repeat(part.chars().count().max(1))
+            ),
+            _ => self.to_string(),
+        };
+
+        if let Error::UnknownClass { .. } = self {
+            let classes: Vec<String> = CLASSES
+                .iter()
+                .map(|(name, _)| format!("[:{}:]", name))
+                .collect();
+            rendered.push_str(&format!("\nSupported classes: {}", classes.join(" ")));
+        }
+
+        rendered
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownClass {
+                operand,
+                name,
+                suggestion,
+                ..
+            } => {
+                write!(f, "Unknown class `[:{}:]` in {}.", name, operand)?;
+                match suggestion {
+                    Some(class) => write!(f, " Did you mean `[:{}:]`?", class),
+                    None => Ok(()),
+                }
+            }
+            Error::Unterminated {
+                text, suggestion, ..
+            } => write!(f, "Unterminated `{}`. Did you mean `{}`?", text, suggestion),
+            Error::InvalidEquivalence {
+                equivalence,
+                suggestion,
+                ..
+            } => write!(
+                f,
+                "An equivalence class holds a single character, but `{}` holds more. Did you mean `{}`?",
+                equivalence, suggestion
+            ),
+            Error::InvalidRange { range, .. } => write!(
+                f,
+                "Invalid range `{}`. The end of a range must not come before its start.",
+                range
+            ),
+            Error::BadEscape { sequence, .. } => match sequence.as_str() {
+                "\\" => write!(
+                    f,
+                    "Incomplete escape sequence. A backslash must be followed by a character."
+                ),
+                s if s.starts_with("\\x") => write!(
+                    f,
+                    "Invalid escape sequence `\\x`. Expected one or two hexadecimal digits."
+                ),
+                s if s.starts_with("\\u{") => write!(
+                    f,
+                    "Invalid escape sequence `{}`. Expected a valid code point.",
+                    s
+                ),
+                s if s.starts_with("\\u") => write!(
+                    f,
+                    "Invalid escape sequence `\\u`. Expected a code point written as `\\u{{...}}`."
+                ),
+                s => write!(f, "Invalid escape sequence `{}`.", s),
+            },
+            Error::InvalidRepeat {
+                repeat, suggestion, ..
+            } => {
+                let count = repeat
+                    .split_once('*')
+                    .map_or("", |(_, count)| count.trim_end_matches(']'));
+                write!(f, "Invalid repeat count `{}` in `{}`.", count, repeat)?;
+                match suggestion {
+                    Some(repeat) => write!(f, " Did you mean `{}`?", repeat),
+                    None => Ok(()),
+                }
+            }
+            Error::MisplacedRepeat {
+                operand: Operand::String1,
+                repeat,
+                ..
+            } => write!(f, "The repeat `{}` can only be used in string2.", repeat),
+            Error::MisplacedRepeat { .. } => {
+                write!(f, "Only one `[c*]` repeat can be used in string2.")
+            }
+            Error::NotAByte { character, .. } => write!(
+                f,
+                "`{}` is not a byte. Use a character from `\\0` to `\\377` in byte mode.",
+                character
+            ),
+            Error::SplitByNormalization {
+                character,
+                normalized,
+                ..
+            } => write!(
+                f,
+                "`{}` normalizes into more than one character (`{}`), so it can never match \
+                 the normalized input.",
+                character, normalized
+            ),
+            Error::Untranslatable { from, to } => {
+                write!(
+                    f,
+                    "Unable to translate {} into {}. Only [:lower:] and [:upper:] can change \
+                     the characters they are paired with.",
+                    from, to
+                )
+            }
+            Error::EmptyString2 => write!(f, "string2 contains no characters."),
+            Error::MissingString2 => write!(f, "These options need string2."),
+            Error::IncompatibleFlags => write!(f, "string2 cannot be used with these options."),
+            Error::InvalidUtf8 { offset } => write!(f, "Invalid UTF-8 at byte offset {}.", offset),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::String1 => write!(f, "string1"),
+            Operand::String2 => write!(f, "string2"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_render_a_caret_under_string1() {
+        let error = Error::UnknownClass {
+            operand: Operand::String1,
+            name: "alhpa".to_string(),
+            offset: 1,
+            suggestion: None,
+        };
+
+        assert_eq!(
+            error.render("a[:alhpa:]", Some("x")),
+            "Unknown class `[:alhpa:]` in string1.\n  a[:alhpa:]\n   ^^^^^^^^^\n\
+             Supported classes: [:alnum:] [:alpha:] [:blank:] [:cntrl:] [:digit:] [:graph:] \
+             [:ideogram:] [:lower:] [:phonogram:] [:print:] [:punct:] [:rune:] [:space:] \
+             [:special:] [:upper:] [:xdigit:]"
+        );
+    }
+
+    #[test]
+    fn unknown_class_can_suggest_a_class() {
+        let error = Error::UnknownClass {
+            operand: Operand::String2,
+            name: "uper".to_string(),
+            offset: 0,
+            suggestion: Some("upper".to_string()),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Unknown class `[:uper:]` in string2. Did you mean `[:upper:]`?"
+        );
+    }
+
+    #[test]
+    fn can_render_a_caret_under_string2() {
+        let error = Error::BadEscape {
+            operand: Operand::String2,
+            sequence: "\\q".to_string(),
+            offset: 3,
+        };
+
+        assert_eq!(
+            error.render("abc", Some("xyz\\q")),
+            "Invalid escape sequence `\\q`.\n  xyz\\q\n     ^^"
+        );
+    }
+
+    #[test]
+    fn caret_counts_characters_rather_than_bytes() {
+        let error = Error::NotAByte {
+            operand: Operand::String1,
+            character: "€".to_string(),
+            offset: 2,
+        };
+
+        assert_eq!(
+            error.render("é€", None),
+            "`€` is not a byte. Use a character from `\\0` to `\\377` in byte mode.\n  é€\n   ^"
+        );
+    }
+
+    #[test]
+    fn errors_without_a_span_render_as_the_message() {
+        assert_eq!(
+            Error::EmptyString2.render("a-z", Some("")),
+            "string2 contains no characters."
+        );
+    }
+}
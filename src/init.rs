@@ -1,11 +1,14 @@
 use crate::args::Cli;
-use anyhow::{anyhow, Result};
+use crate::error::Error;
 
 /// Defines the different modes that the application can be run in.
 #[derive(Debug)]
 pub enum Mode {
     /// Translate the characters from string1 to string2
     Translate,
+    /// Translate the characters from string1 to string2 and then remove the
+    /// duplicate characters listed in string2
+    TranslateCompress,
     /// Delete the characters listed in string1
     Delete,
     /// Remove the duplicate characters listed in string1
@@ -13,29 +16,48 @@ pub enum Mode {
     /// Delete the characters listed in string1 and remove
     /// duplicate characters listed in string2
     DeleteCompress,
+    /// Only transliterate the characters listed in string1 into ASCII
+    Transliterate,
 }
 
-/// Decide what mode to run the application in
-pub fn init(args: &Cli) -> Result<Mode> {
-    if args.string2.is_none() && args.delete && !args.squeeze {
-        Ok(Mode::Delete)
-    } else if args.string2.is_none() && !args.delete && args.squeeze {
-        Ok(Mode::Compress)
-    } else if args.string2.is_some() && !args.delete {
-        Ok(Mode::Translate)
-    } else if args.string2.is_some() && args.delete && args.squeeze {
-        Ok(Mode::DeleteCompress)
-    } else {
-        Err(anyhow!(
-            "usage: 
-            tr [-Ccsu] string1 string2
-            tr [-Ccu] -d string1
-            tr [-Ccu] -s string1
-            tr [-Ccu] -ds string1 string2"
-        ))
+impl Mode {
+    /// Work out the mode from whether string2 was given and the delete,
+    /// squeeze and ascii flags. Returns `None` if the combination is not valid.
+    pub fn new(string2: bool, delete: bool, squeeze: bool, ascii: bool) -> Option<Mode> {
+        match (string2, delete, squeeze) {
+            (false, false, false) if ascii => Some(Mode::Transliterate),
+            (false, true, false) => Some(Mode::Delete),
+            (false, false, true) => Some(Mode::Compress),
+            (true, false, false) => Some(Mode::Translate),
+            (true, false, true) => Some(Mode::TranslateCompress),
+            (true, true, true) => Some(Mode::DeleteCompress),
+            _ => None,
+        }
     }
 }
 
+/// Shows the ways string1, string2 and the flags can be combined
+pub const USAGE: &str = "usage:
+    tr [-Ccsu] string1 string2
+    tr [-Ccu] -d string1
+    tr [-Ccu] -s string1
+    tr [-Ccu] -ds string1 string2
+    tr [-Ccu] --ascii [string1]";
+
+/// Decide what mode to run the application in
+pub fn init(args: &Cli) -> Result<Mode, Error> {
+    Mode::new(
+        args.string2.is_some(),
+        args.delete,
+        args.squeeze,
+        args.ascii,
+    )
+    .ok_or(match args.string2 {
+        Some(_) => Error::IncompatibleFlags,
+        None => Error::MissingString2,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +76,7 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -71,6 +94,7 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -88,6 +112,7 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -105,11 +130,12 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
 
-        assert!(matches!(mode, Mode::Translate));
+        assert!(matches!(mode, Mode::TranslateCompress));
     }
 
     #[test]
@@ -122,6 +148,7 @@ mod tests {
             unbuffered: true,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -139,11 +166,30 @@ mod tests {
             unbuffered: true,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
 
-        assert!(matches!(mode, Mode::Translate));
+        assert!(matches!(mode, Mode::TranslateCompress));
+    }
+
+    #[test]
+    fn can_run_in_translate_and_compress_mode() {
+        let args = Cli {
+            complement1: false,
+            complement2: false,
+            delete: false,
+            squeeze: true,
+            unbuffered: false,
+            string1: "a-z".to_string(),
+            string2: Some("X".to_string()),
+            ..Default::default()
+        };
+
+        let mode = init(&args).unwrap();
+
+        assert!(matches!(mode, Mode::TranslateCompress));
     }
 
     // ************************************************************************
@@ -160,6 +206,7 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: None,
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -177,6 +224,7 @@ mod tests {
             unbuffered: true,
             string1: "c".to_string(),
             string2: None,
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -194,22 +242,12 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let res = init(&args);
 
-        assert!(res.is_err());
-
-        let error = res.unwrap_err();
-        let root_cause = error.root_cause();
-        assert_eq!(
-            format!("{}", root_cause),
-            "usage: 
-            tr [-Ccsu] string1 string2
-            tr [-Ccu] -d string1
-            tr [-Ccu] -s string1
-            tr [-Ccu] -ds string1 string2"
-        );
+        assert!(matches!(res, Err(Error::IncompatibleFlags)));
     }
 
     #[test]
@@ -222,22 +260,12 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: None,
+            ..Default::default()
         };
 
         let res = init(&args);
 
-        assert!(res.is_err());
-
-        let error = res.unwrap_err();
-        let root_cause = error.root_cause();
-        assert_eq!(
-            format!("{}", root_cause),
-            "usage: 
-            tr [-Ccsu] string1 string2
-            tr [-Ccu] -d string1
-            tr [-Ccu] -s string1
-            tr [-Ccu] -ds string1 string2"
-        );
+        assert!(matches!(res, Err(Error::MissingString2)));
     }
 
     #[test]
@@ -250,22 +278,12 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: None,
+            ..Default::default()
         };
 
         let res = init(&args);
 
-        assert!(res.is_err());
-
-        let error = res.unwrap_err();
-        let root_cause = error.root_cause();
-        assert_eq!(
-            format!("{}", root_cause),
-            "usage: 
-            tr [-Ccsu] string1 string2
-            tr [-Ccu] -d string1
-            tr [-Ccu] -s string1
-            tr [-Ccu] -ds string1 string2"
-        );
+        assert!(matches!(res, Err(Error::MissingString2)));
     }
 
     #[test]
@@ -278,22 +296,12 @@ mod tests {
             unbuffered: true,
             string1: "c".to_string(),
             string2: None,
+            ..Default::default()
         };
 
         let res = init(&args);
 
-        assert!(res.is_err());
-
-        let error = res.unwrap_err();
-        let root_cause = error.root_cause();
-        assert_eq!(
-            format!("{}", root_cause),
-            "usage: 
-            tr [-Ccsu] string1 string2
-            tr [-Ccu] -d string1
-            tr [-Ccu] -s string1
-            tr [-Ccu] -ds string1 string2"
-        );
+        assert!(matches!(res, Err(Error::MissingString2)));
     }
 
     // ************************************************************************
@@ -310,6 +318,7 @@ mod tests {
             unbuffered: false,
             string1: "c".to_string(),
             string2: None,
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
@@ -331,10 +340,60 @@ mod tests {
             unbuffered: true,
             string1: "c".to_string(),
             string2: Some("C".to_string()),
+            ..Default::default()
         };
 
         let mode = init(&args).unwrap();
 
         assert!(matches!(mode, Mode::DeleteCompress));
     }
+
+    // ************************************************************************
+    // transliterate mode tests
+    // ************************************************************************
+
+    #[test]
+    fn can_run_in_transliterate_mode() {
+        let args = Cli {
+            ascii: true,
+            string1: "[:rune:]".to_string(),
+            string2: None,
+            ..Default::default()
+        };
+
+        let mode = init(&args).unwrap();
+
+        assert!(matches!(mode, Mode::Transliterate));
+    }
+
+    #[test]
+    fn the_ascii_flag_does_not_change_the_other_modes() {
+        let args = Cli {
+            ascii: true,
+            delete: true,
+            string1: "c".to_string(),
+            string2: None,
+            ..Default::default()
+        };
+
+        let mode = init(&args).unwrap();
+
+        assert!(matches!(mode, Mode::Delete));
+    }
+
+    #[test]
+    fn the_ascii_flag_still_needs_string2_to_translate() {
+        let args = Cli {
+            ascii: true,
+            squeeze: true,
+            delete: true,
+            string1: "c".to_string(),
+            string2: None,
+            ..Default::default()
+        };
+
+        let res = init(&args);
+
+        assert!(matches!(res, Err(Error::MissingString2)));
+    }
 }
@@ -1,3 +1,11 @@
+pub mod adapters;
 pub mod args;
+#[cfg(feature = "tokio")]
+pub mod async_adapters;
+pub mod error;
 pub mod init;
 pub mod run;
+pub mod set;
+mod table;
+mod tables;
+pub mod translator;
@@ -1,22 +1,64 @@
-use anyhow::Result;
+use cctr::error::Error;
 use clap::Parser;
-use log::error;
+use log::{LevelFilter, Log, Metadata, Record};
 use std::process::exit;
 
-fn main() -> Result<()> {
+/// Writes log messages to stderr, prefixed with their level
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{}: {}",
+                record.level().as_str().to_lowercase(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Send log messages at or above `level` to stderr
+fn init_logger(level: LevelFilter) {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+fn main() {
     // Get the args from the command line.
     let mut args = cctr::args::Cli::parse();
 
-    // Decide what mode to run the application in
+    init_logger(args.verbose.log_level_filter());
+
+    // Decide what mode to run the application in. Errors are always shown,
+    // however quiet the logger is.
     let mode = match cctr::init::init(&args) {
         Ok(mode) => mode,
         Err(e) => {
-            error!("{:?}", e);
+            eprintln!("{}\n{}", e, cctr::init::USAGE);
             exit(2)
         }
     };
 
-    cctr::run::run(&mut args, &mode)?;
+    if let Err(e) = cctr::run::run(&mut args, &mode) {
+        // Point at the part of string1 or string2 that is at fault
+        match e.downcast_ref::<Error>() {
+            Some(error) if error.span().is_some() => {
+                eprintln!("{}", error.render(&args.string1, args.string2.as_deref()))
+            }
+            _ => eprintln!("{:#}", e),
+        }
+        exit(1)
+    }
 
     exit(exitcode::OK);
 }
@@ -1,715 +1,159 @@
-use crate::args::Cli;
+use crate::args::{CaseRules, Cli};
+use crate::error::Error;
 use crate::init::Mode;
+use crate::translator::Translator;
 
-use anyhow::{anyhow, Context, Result};
-use regex::Regex;
-use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, IsTerminal, Write};
-use std::iter::zip;
+use anyhow::{bail, Context, Result};
+use log::debug;
+use std::env;
+use std::io::{stdin, stdout, BufRead, BufWriter, IsTerminal, Write};
 
 /// Translate, delete and/or compress the strings held in `args`. Use `mode` to
 /// decide whether to translate, delete and/or compress. Write the output to stdout.
+///
+/// The input is processed as a stream of chunks, so newlines are translated,
+/// deleted and squeezed like any other character and the output is the input
+/// with only the requested changes. The output is buffered for the whole run
+/// unless `args.unbuffered` is set, in which case every chunk is flushed as soon
+/// as it has been processed.
 pub fn run(args: &mut Cli, mode: &Mode) -> Result<()> {
-    if stdin().is_terminal() {
-        // Request a line of text from the user
-        let mut line = String::new();
-        stdin().read_line(&mut line)?;
-
-        process_line(line, args, mode, BufWriter::new(stdout()))?;
-    } else {
-        // Read the lines of text received from another cli application
-        let reader = BufReader::new(stdin().lock());
-        reader
-            .lines()
-            .try_for_each(|line| process_line(line?, args, mode, BufWriter::new(stdout())))?;
+    // The C locale has no notion of multibyte characters
+    if !args.bytes && is_c_locale() {
+        debug!("The locale is C, so the input is read as bytes");
+        args.bytes = true;
     }
 
-    Ok(())
-}
-
-/// Translate, delete and/or compress a single line
-fn process_line(line: String, args: &mut Cli, mode: &Mode, mut writer: impl Write) -> Result<()> {
-    // Extract a list of patterns to process from string1
-    let string1 = &mut args.string1;
-    let patterns1 = get_patterns(string1)?;
-
-    // Extract a list of patterns to process from string2
-    // (only if in Translate or DeleteCompress mode)
-    let patterns2 = match mode {
-        Mode::Translate => {
-            let string2 = &mut args.string2.clone().unwrap();
-            get_patterns(string2)?
-        }
-        Mode::Delete => Vec::new(),
-        Mode::Compress => Vec::new(),
-        Mode::DeleteCompress => {
-            let string2 = &mut args.string2.clone().unwrap();
-            get_patterns(string2)?
-        }
-    };
-
-    let line = match mode {
-        Mode::Translate => translate(line, patterns1, patterns2)?,
-        Mode::Delete => delete(line, patterns1)?,
-        Mode::Compress => compress(line, patterns1)?,
-        Mode::DeleteCompress => delete_and_compress(line, patterns1, patterns2)?,
-    };
-
-    writeln!(writer, "{}", line).with_context(|| "Unable to write line to writer.".to_string())
-}
-
-/// Translate the given line using string1 and string2 in the args. Write the translated line
-/// to writer.
-fn translate(
-    mut line: String,
-    mut graphemes1: Vec<Pattern>,
-    mut graphemes2: Vec<Pattern>,
-) -> Result<String> {
-    // Make sure both strings are the same length. If not, pad the shorter one
-    // with whitespace chracters
-    if graphemes1.len() > graphemes2.len() {
-        graphemes2.resize(graphemes1.len(), graphemes2.last().unwrap().clone());
-    } else {
-        graphemes1.truncate(graphemes2.len());
-    }
-
-    // Replace all chars found in string1 with the chars found in string2
-    for (char1, char2) in zip(graphemes1, graphemes2) {
-        line = match char1 {
-            Pattern::Alnum => translate_alphanumerics(line, char2)?,
-            Pattern::Alpha => translate_alphabetic(line, char2)?,
-            Pattern::Blank => translate_blank(line, char2)?,
-            Pattern::Cntrl => translate_control(line, char2)?,
-            Pattern::Digit => translate_digit(line, char2)?,
-            Pattern::Lower => translate_lowercase(line, char2)?,
-            Pattern::Space => translate_blank(line, char2)?,
-            Pattern::Upper => translate_uppercase(line, char2)?,
-            Pattern::Char(c) => translate_char(line, c, char2)?,
+    // Only ASCII letters have case in byte mode, so the case rules of a locale
+    // given on the command line would be ignored
+    if let Some(locale) = args.locale.as_deref() {
+        if args.bytes && CaseRules::from_locale(locale) != CaseRules::Ascii {
+            bail!(
+                "The locale `{}` cannot be used in byte mode, where only ASCII letters have case.",
+                locale
+            );
         }
     }
 
-    Ok(line)
-}
-
-/// Defines the patterns in string1 and string2 to process
-#[derive(Debug, Clone)]
-pub enum Pattern {
-    /// Represents a grapheme (character)
-    Char(char),
-    Alnum,
-    Alpha,
-    Blank,
-    Cntrl,
-    Digit,
-    Lower,
-    Space,
-    Upper,
-}
-
-/// Extract graphemes (characters) and classes ready to translate a string by
-fn get_patterns(string: &mut str) -> Result<Vec<Pattern>> {
-    // match patterns that are either words flanked by [::]
-    // or match against single characters
-    let re = Regex::new(r"\[:([^:]+):]|.")?;
-    let str_patterns: Vec<&str> = re.find_iter(string).map(|m| m.as_str()).collect();
-
-    // start a vector to hold the patterns in
-    let mut patterns = Vec::new();
-
-    // loop through the patterns and parse each pattern into a Pattern type
-    for str_pattern in str_patterns {
-        if str_pattern.len() > 1 {
-            match str_pattern {
-                "[:alnum:]" => patterns.push(Pattern::Alnum),
-                "[:alpha:]" => patterns.push(Pattern::Alpha),
-                "[:blank:]" => patterns.push(Pattern::Blank),
-                "[:cntrl:]" => patterns.push(Pattern::Cntrl),
-                "[:digit:]" => patterns.push(Pattern::Digit),
-                "[:lower:]" => patterns.push(Pattern::Lower),
-                "[:space:]" => patterns.push(Pattern::Space),
-                "[:upper:]" => patterns.push(Pattern::Upper),
-                _ => return Err(anyhow!("Invalid class.")),
-            }
-        } else {
-            patterns.push(Pattern::Char(str_pattern.chars().next().unwrap()));
-        }
+    // Case rules follow the locale unless one is given
+    if args.locale.is_none() {
+        args.locale = env_locale();
     }
 
-    Ok(patterns)
-}
-
-/// Translate the alphanumeric characters
-fn translate_alphanumerics(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line,
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() {
-                    char::from_u32((c as u32) + 10).unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c.is_alphanumeric() { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c.is_alphanumeric() { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() & !c.is_ascii_digit() {
-                    '9'
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() {
-                    c.to_lowercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c.is_alphanumeric() { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line
-            .chars()
-            .map(|c| if c.is_alphanumeric() { new_c } else { c })
-            .collect(),
-    };
+    // Compile the sets before reading any input so that mistakes in them are
+    // reported straight away
+    let translator = translator(args, mode)?;
 
-    Ok(line)
-}
+    if stdin().is_terminal() {
+        // Request a line of text from the user
+        let mut line = Vec::new();
+        stdin().lock().read_until(b'\n', &mut line)?;
+
+        let output = translator.apply_bytes(&line)?;
+        return stdout()
+            .lock()
+            .write_all(&output)
+            .with_context(|| "Unable to write output to stdout".to_string());
+    }
 
-/// Translate the alphabetic characters
-fn translate_alphabetic(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| {
-                if c.is_alphabetic() {
-                    char::from_u32((c as u32) + 10).unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Alpha => line,
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c.is_alphabetic() { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c.is_alphabetic() { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line
-            .chars()
-            .map(|c| {
-                if c.is_alphabetic() & !c.is_ascii_digit() {
-                    '9'
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c.is_alphabetic() {
-                    c.to_lowercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c.is_alphabetic() { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c.is_alphabetic() {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line
-            .chars()
-            .map(|c| if c.is_alphabetic() { new_c } else { c })
-            .collect(),
+    // Process the text received from another cli application
+    let result = match args.unbuffered {
+        true => translator.process(stdin().lock(), Unbuffered(stdout().lock())),
+        false => translator.process(stdin().lock(), BufWriter::new(stdout().lock())),
     };
 
-    Ok(line)
+    result.with_context(|| "Unable to translate stdin".to_string())
 }
 
-/// Translate whitespace characters (' ') into the gven pattern
-fn translate_blank(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| if c == ' ' { '2' } else { c })
-            .collect(),
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| if c == ' ' { 'C' } else { c })
-            .collect(),
-        Pattern::Blank => line,
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c == ' ' { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line
-            .chars()
-            .map(|c| if c == ' ' { '2' } else { c })
-            .collect(),
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c == ' ' {
-                    c.to_lowercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line,
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c == ' ' {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line.replace(' ', &new_c.to_string()),
-    };
-
-    Ok(line)
+/// Build a translator from the sets and flags in `args` for a mode worked out
+/// by `init`
+fn translator(args: &Cli, mode: &Mode) -> Result<Translator, Error> {
+    Translator::builder(&args.string1)
+        .complement(args.complement1 || args.complement2)
+        .collate(args.complement2)
+        .delete_marks(args.delete_marks)
+        .bytes(args.bytes)
+        .graphemes(args.graphemes)
+        .normalize(args.normalize)
+        .normalize_output(args.normalize_output)
+        .ascii(args.ascii)
+        .ascii_fallback(args.ascii_fallback)
+        .invalid_utf8(args.invalid_utf8)
+        .case_mapping(args.case_mapping)
+        .case_rules(CaseRules::from_locale(
+            args.locale.as_deref().unwrap_or_default(),
+        ))
+        .string2(args.string2.clone().unwrap_or_default())
+        .compile(mode)
 }
 
-/// Translate the control characters
-fn translate_control(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| if c.is_control() { 'A' } else { c })
-            .collect(),
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| if c.is_control() { 'K' } else { c })
-            .collect(),
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c.is_control() { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line,
-        Pattern::Digit => line
-            .chars()
-            .map(|c| {
-                if c.is_control() & !c.is_ascii_digit() {
-                    '9'
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c.is_control() {
-                    c.to_lowercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c.is_control() { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c.is_control() {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line
-            .chars()
-            .map(|c| if c.is_control() { new_c } else { c })
-            .collect(),
-    };
-
-    Ok(line)
-}
+/// A writer that flushes everything written to it straight away
+struct Unbuffered<W: Write>(W);
 
-/// Translate the digits into the given pattern
-fn translate_digit(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| {
-                if c.is_numeric() {
-                    char::from_u32(c as u32).unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| {
-                if c.is_numeric() {
-                    char::from_u32(c as u32).unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c.is_numeric() { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c.is_numeric() { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line,
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c.is_numeric() {
-                    char::from_u32(c.to_digit(10).unwrap() + 97)
-                        .unwrap()
-                        .to_lowercase()
-                        .next()
-                        .unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c.is_numeric() { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c.is_numeric() {
-                    char::from_u32(c.to_digit(10).unwrap() + 65)
-                        .unwrap()
-                        .to_uppercase()
-                        .next()
-                        .unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line
-            .chars()
-            .map(|c| if c.is_numeric() { new_c } else { c })
-            .collect(),
-    };
+impl<W: Write> Write for Unbuffered<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.0.write(buf)?;
+        self.0.flush()?;
+        Ok(len)
+    }
 
-    Ok(line)
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
 }
 
-/// Translate the lowercase characters into the given pattern
-fn translate_lowercase(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| {
-                if c.is_lowercase() {
-                    char::from_u32((c as u32) - 49).unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| {
-                if c.is_lowercase() {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c.is_lowercase() { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c.is_lowercase() { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line
-            .chars()
-            .map(|c| {
-                if c.is_lowercase() {
-                    if c as u32 <= 48 {
-                        char::from_u32((c as u32) - 49).unwrap()
-                    } else {
-                        '9'
-                    }
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Lower => line,
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c.is_lowercase() { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c.is_lowercase() {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line
-            .chars()
-            .map(|c| if c.is_lowercase() { new_c } else { c })
-            .collect(),
-    };
-
-    Ok(line)
+/// Returns true if the locale set in the environment is `C` or `POSIX`
+fn is_c_locale() -> bool {
+    matches!(env_locale().as_deref(), Some("C" | "POSIX"))
 }
 
-/// Translate the lowercase characters into the given pattern
-fn translate_uppercase(mut line: String, pattern: Pattern) -> Result<String> {
-    line = match pattern {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| {
-                if c.is_uppercase() {
-                    char::from_u32((c as u32) - 49).unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| {
-                if c.is_uppercase() {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c.is_uppercase() { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c.is_uppercase() { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line
-            .chars()
-            .map(|c| {
-                if c.is_uppercase() {
-                    if c as u32 <= 48 {
-                        char::from_u32((c as u32) - 49).unwrap()
-                    } else {
-                        '9'
-                    }
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c.is_uppercase() {
-                    c.to_lowercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c.is_uppercase() { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line,
-        Pattern::Char(new_c) => line
-            .chars()
-            .map(|c| if c.is_uppercase() { new_c } else { c })
-            .collect(),
-    };
-
-    Ok(line)
+/// Returns the locale for character handling set in the environment. As with
+/// setlocale, `LC_ALL` overrides `LC_CTYPE`, which overrides `LANG`.
+fn env_locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
 }
 
-/// Translate a character (pattern1) into pattern2
-fn translate_char(mut line: String, pattern1: char, pattern2: Pattern) -> Result<String> {
-    line = match pattern2 {
-        Pattern::Alnum => line
-            .chars()
-            .map(|c| if c == pattern1 { '0' } else { c })
-            .collect(),
-        Pattern::Alpha => line
-            .chars()
-            .map(|c| if c == pattern1 { 'A' } else { c })
-            .collect(),
-        Pattern::Blank => line
-            .chars()
-            .map(|c| if c == pattern1 { ' ' } else { c })
-            .collect(),
-        Pattern::Cntrl => line
-            .chars()
-            .map(|c| if c == pattern1 { ' ' } else { c })
-            .collect(),
-        Pattern::Digit => line
-            .chars()
-            .map(|c| if c == pattern1 { '0' } else { c })
-            .collect(),
-        Pattern::Lower => line
-            .chars()
-            .map(|c| {
-                if c == pattern1 {
-                    c.to_lowercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Space => line
-            .chars()
-            .map(|c| if c == pattern1 { ' ' } else { c })
-            .collect(),
-        Pattern::Upper => line
-            .chars()
-            .map(|c| {
-                if c == pattern1 {
-                    c.to_uppercase().next().unwrap()
-                } else {
-                    c
-                }
-            })
-            .collect(),
-        Pattern::Char(new_c) => line.replace(pattern1, &new_c.to_string()),
-    };
-
-    Ok(line)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::{AsciiFallback, CaseMapping, InvalidUtf8, Normalization};
+    use crate::set::{Pattern, CLASSES};
+    use crate::translator::Stream;
+
+    /// Process a single line, writing a newline after it the way the line
+    /// based tests expect
+    fn process_line(line: String, args: &mut Cli, mode: &Mode, writer: impl Write) -> Result<()> {
+        process_bytes(line.as_bytes(), args, mode, writer)
+    }
 
-/// Remove the patterns specified from the line parameter
-fn delete(mut line: String, patterns: Vec<Pattern>) -> Result<String> {
-    // Replace all chars found in string1 with the chars found in string2
-    for pattern in patterns {
-        line = match pattern {
-            Pattern::Alnum => line.replace(char::is_alphanumeric, ""),
-            Pattern::Alpha => line.replace(char::is_alphabetic, ""),
-            Pattern::Blank => line.replace(char::is_whitespace, ""),
-            Pattern::Cntrl => line.replace(char::is_control, ""),
-            Pattern::Digit => line.replace(char::is_numeric, ""),
-            Pattern::Lower => line.replace(char::is_lowercase, ""),
-            Pattern::Space => line.replace(char::is_whitespace, ""),
-            Pattern::Upper => line.replace(char::is_uppercase, ""),
-            Pattern::Char(c) => line.replace(c, ""),
-        }
+    /// Process a single line of bytes as one chunk, writing a newline after it
+    fn process_bytes(
+        line: &[u8],
+        args: &mut Cli,
+        mode: &Mode,
+        mut writer: impl Write,
+    ) -> Result<()> {
+        let output = translator(args, mode)?.apply_bytes(line)?;
+        writer.write_all(&output)?;
+
+        writeln!(writer).with_context(|| "Unable to write line to writer.".to_string())
     }
 
-    Ok(line)
-}
+    /// Process input that arrives in several chunks and return the output
+    fn process_chunks(chunks: &[&[u8]], args: &Cli, mode: &Mode) -> Result<Vec<u8>> {
+        let translator = translator(args, mode)?;
+        let mut stream = Stream::default();
+        let mut writer = Vec::new();
 
-/// Remove repeating patterns
-fn compress(mut line: String, patterns: Vec<Pattern>) -> Result<String> {
-    for pattern in patterns {
-        let mut new_line = String::new();
-
-        // Loop through characters in string adding each one to a new copy
-        // of the string unless the last character added to the string was
-        // the same.
-        for c in line.chars() {
-            if new_line.ends_with(c) && check_char(&pattern, &c) {
-                continue;
-            } else {
-                new_line.push(c);
-            }
+        for chunk in chunks {
+            stream.write(&translator, chunk, &mut writer)?;
         }
+        stream.finish(&translator, &mut writer)?;
 
-        // Replace the line with the sequence of repeated characters removed
-        line = new_line;
-    }
-
-    Ok(line)
-}
-
-/// Returns true if a character should be processed
-fn check_char(pattern: &Pattern, character: &char) -> bool {
-    match pattern {
-        Pattern::Alnum => character.is_alphanumeric(),
-        Pattern::Alpha => character.is_alphabetic(),
-        Pattern::Blank => character.is_whitespace(),
-        Pattern::Cntrl => character.is_control(),
-        Pattern::Digit => character.is_numeric(),
-        Pattern::Lower => character.is_lowercase(),
-        Pattern::Space => character.is_whitespace(),
-        Pattern::Upper => character.is_uppercase(),
-        Pattern::Char(c) => c == character,
+        Ok(writer)
     }
-}
-
-/// Run the delete and compress functions together over a single line of characters
-fn delete_and_compress(
-    mut line: String,
-    patterns1: Vec<Pattern>,
-    patterns2: Vec<Pattern>,
-) -> Result<String> {
-    line = delete(line, patterns1)?;
-    line = compress(line, patterns2)?;
-
-    Ok(line)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     // ************************************************************************
     // translate tests (characters)
@@ -896,6 +340,132 @@ mod tests {
         assert_eq!(writer, b"[coding challenge]\n");
     }
 
+    #[test]
+    fn translations_do_not_chain() {
+        let line = "abc".to_string();
+
+        let mut args = Cli {
+            string1: "ab".to_string(),
+            string2: Some("bc".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"bcc\n");
+    }
+
+    #[test]
+    fn can_swap_characters() {
+        let line = "abba".to_string();
+
+        let mut args = Cli {
+            string1: "ab".to_string(),
+            string2: Some("ba".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"baab\n");
+    }
+
+    #[test]
+    fn can_translate_rot13() {
+        let line = "Hello, World".to_string();
+
+        let mut args = Cli {
+            string1: "a-zA-Z".to_string(),
+            string2: Some("n-za-mN-ZA-M".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Uryyb, Jbeyq\n");
+    }
+
+    #[test]
+    fn last_mapping_wins_when_string1_repeats_a_character() {
+        let line = "banana".to_string();
+
+        let mut args = Cli {
+            string1: "aa".to_string(),
+            string2: Some("xy".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"bynyny\n");
+    }
+
+    #[test]
+    fn later_class_wins_over_earlier_character() {
+        let line = "abc XYZ".to_string();
+
+        let mut args = Cli {
+            string1: "a[:lower:]".to_string(),
+            string2: Some("x_".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"___ XYZ\n");
+    }
+
+    #[test]
+    fn later_character_wins_over_earlier_class() {
+        let line = "abc XYZ".to_string();
+
+        let mut args = Cli {
+            string1: "[:lower:]a".to_string(),
+            string2: Some("[_*]x".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"x__ XYZ\n");
+    }
+
+    #[test]
+    fn can_translate_non_ascii_characters_once() {
+        let line = "αβγ".to_string();
+
+        let mut args = Cli {
+            string1: "αβ".to_string(),
+            string2: Some("βγ".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "βγγ\n".as_bytes());
+    }
+
     // ************************************************************************
     // translate tests (classes)
     // ************************************************************************
@@ -1045,7 +615,7 @@ mod tests {
     }
 
     #[test]
-    fn can_translate_alphanumeric_to_alphabetic() {
+    fn translating_alphanumeric_to_alphabetic_returns_error() {
         let line = "coding challenge".to_string();
 
         let mut args = Cli {
@@ -1058,12 +628,14 @@ mod tests {
 
         let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"mynsxq mrkvvoxqo\n");
+        assert!(matches!(
+            result.unwrap_err().downcast::<Error>(),
+            Ok(Error::Untranslatable { .. })
+        ));
     }
 
     #[test]
-    fn can_translate_alphanumeric_to_blank() {
+    fn translating_alphanumeric_to_blank_returns_error() {
         let line = "coding challenge".to_string();
 
         let mut args = Cli {
@@ -1076,12 +648,14 @@ mod tests {
 
         let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"                \n");
+        assert!(matches!(
+            result.unwrap_err().downcast::<Error>(),
+            Ok(Error::Untranslatable { .. })
+        ));
     }
 
     #[test]
-    fn can_translate_alphanumeric_to_control_character() {
+    fn translating_alphanumeric_to_control_character_returns_error() {
         let line = "coding challenge".to_string();
 
         let mut args = Cli {
@@ -1094,13 +668,15 @@ mod tests {
 
         let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"                \n");
+        assert!(matches!(
+            result.unwrap_err().downcast::<Error>(),
+            Ok(Error::Untranslatable { .. })
+        ));
     }
 
     #[test]
-    fn can_translate_alphanumeric_to_digit() {
-        let line = "1oding challenge".to_string();
+    fn translating_alphanumeric_to_digit_returns_error() {
+        let line = "coding challenge".to_string();
 
         let mut args = Cli {
             string1: "[:alnum:]".to_string(),
@@ -1112,8 +688,10 @@ mod tests {
 
         let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"199999 999999999\n");
+        assert!(matches!(
+            result.unwrap_err().downcast::<Error>(),
+            Ok(Error::Untranslatable { .. })
+        ));
     }
 
     #[test]
@@ -1135,7 +713,7 @@ mod tests {
     }
 
     #[test]
-    fn can_translate_alphanumeric_to_space() {
+    fn translating_alphanumeric_to_space_returns_error() {
         let line = "coding challenge".to_string();
 
         let mut args = Cli {
@@ -1148,8 +726,10 @@ mod tests {
 
         let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"                \n");
+        assert!(matches!(
+            result.unwrap_err().downcast::<Error>(),
+            Ok(Error::Untranslatable { .. })
+        ));
     }
 
     #[test]
@@ -1189,7 +769,7 @@ mod tests {
     }
 
     #[test]
-    fn can_translate_digits_to_control_chars() {
+    fn translating_digits_to_control_chars_returns_error() {
         let line = "01234 challenge".to_string();
 
         let mut args = Cli {
@@ -1202,17 +782,19 @@ mod tests {
 
         let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"      challenge\n");
+        assert!(matches!(
+            result.unwrap_err().downcast::<Error>(),
+            Ok(Error::Untranslatable { .. })
+        ));
     }
 
     #[test]
-    fn can_translate_digits_to_lowercase_chars() {
+    fn digits_pair_with_lowercase_chars_by_position() {
         let line = "01234 challenge".to_string();
 
         let mut args = Cli {
             string1: "[:digit:]".to_string(),
-            string2: Some("[:lower:]".to_string()),
+            string2: Some("a-j".to_string()),
             ..Default::default()
         };
 
@@ -1225,12 +807,12 @@ mod tests {
     }
 
     #[test]
-    fn can_translate_digits_to_uppercase_chars() {
+    fn digits_pair_with_uppercase_chars_by_position() {
         let line = "01234 challenge".to_string();
 
         let mut args = Cli {
             string1: "[:digit:]".to_string(),
-            string2: Some("[:upper:]".to_string()),
+            string2: Some("A-J".to_string()),
             ..Default::default()
         };
 
@@ -1242,151 +824,2575 @@ mod tests {
         assert_eq!(writer, b"ABCDE challenge\n");
     }
 
-    // ************************************************************************
-    // delete tests
-    // ************************************************************************
     #[test]
-    fn can_delete_single_characters() {
-        let line = "Coding challenge".to_string();
+    fn can_translate_punctuation_class() {
+        let line = "coding, challenge!".to_string();
 
         let mut args = Cli {
-            string1: "C".to_string(),
+            string1: "[:punct:]".to_string(),
+            string2: Some("_".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"oding challenge\n");
+        assert_eq!(writer, b"coding_ challenge_\n");
     }
 
     #[test]
-    fn can_delete_many_characters() {
-        let line = "Coding challenge".to_string();
+    fn can_translate_unicode_punctuation_class() {
+        let line = "¿qué? €5".to_string();
 
         let mut args = Cli {
-            string1: "Cdg".to_string(),
+            string1: "[:punct:]".to_string(),
+            string2: Some("_".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"oin challene\n");
+        assert_eq!(writer, "_qué_ _5\n".as_bytes());
     }
 
     #[test]
-    fn can_delete_nothing() {
-        let line = "Coding challenge".to_string();
+    fn can_translate_hex_digits_to_upper() {
+        let line = "0xff 0xab".to_string();
 
         let mut args = Cli {
-            string1: "".to_string(),
+            string1: "[:xdigit:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"Coding challenge\n");
+        assert_eq!(writer, b"0xFF 0xAB\n");
     }
 
     #[test]
-    fn can_delete_everything() {
-        let line = "Coding challenge".to_string();
+    fn can_translate_graph_class() {
+        let line = "a b\tc".to_string();
 
         let mut args = Cli {
-            string1: "Coding challenge".to_string(),
+            string1: "[:graph:]".to_string(),
+            string2: Some("x".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"\n");
+        assert_eq!(writer, b"x x\tx\n");
     }
 
     #[test]
-    fn can_delete_digits() {
-        let line = "123 challenge".to_string();
+    fn can_translate_phonogram_class() {
+        let line = "漢字とカタカナ".to_string();
 
         let mut args = Cli {
-            string1: "123".to_string(),
+            string1: "[:phonogram:]".to_string(),
+            string2: Some("_".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b" challenge\n");
+        assert_eq!(writer, "漢字_____\n".as_bytes());
     }
 
     #[test]
-    fn can_delete_whitespace() {
-        let line = "Coding challenge".to_string();
+    fn can_translate_special_class() {
+        let line = "¿qué? 5€".to_string();
 
         let mut args = Cli {
-            string1: " ".to_string(),
+            string1: "[:special:]".to_string(),
+            string2: Some("?".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"Codingchallenge\n");
+        assert_eq!(writer, "?qué? 5?\n".as_bytes());
     }
 
     #[test]
-    fn can_delete_special_chars() {
-        let line = "Coding@challenge".to_string();
+    fn translating_punctuation_to_digits_returns_error() {
+        let line = "coding, challenge!".to_string();
 
         let mut args = Cli {
-            string1: "@".to_string(),
+            string1: "[:punct:]".to_string(),
+            string2: Some("[:digit:]".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
 
-        assert!(result.is_ok());
-        assert_eq!(writer, b"Codingchallenge\n");
+        assert_eq!(
+            format!("{}", result.unwrap_err()),
+            "Unable to translate [:punct:] into [:digit:]. Only [:lower:] and [:upper:] can \
+             change the characters they are paired with."
+        );
     }
 
-    #[test]
-    fn can_delete_upper_class() {
-        let line = "CoDinG challenge".to_string();
-
-        let mut args = Cli {
-            string1: "[:upper:]".to_string(),
-            ..Default::default()
-        };
-
-        let mut writer = Vec::new();
+    // ************************************************************************
+    // translate tests (conformance)
+    // ************************************************************************
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+    /// One pattern of every kind, written the way it appears in a set string
+    fn every_kind_of_pattern() -> Vec<Pattern> {
+        let mut patterns = vec![
+            Pattern::Char('a'),
+            Pattern::Range('a', 'c'),
+            Pattern::Equivalence('e'),
+            Pattern::Repeat('x', Some(2)),
+            Pattern::Cluster("e\u{301}".to_string()),
+        ];
+        patterns.extend(CLASSES.iter().map(|(_, class)| class.clone()));
+
+        patterns
+    }
 
-        assert!(result.is_ok());
+    #[test]
+    fn every_pair_of_patterns_translates_or_returns_error() {
+        for pattern1 in every_kind_of_pattern() {
+            for pattern2 in every_kind_of_pattern() {
+                let result = Translator::builder(pattern1.to_string())
+                    .string2(pattern2.to_string())
+                    .graphemes(true)
+                    .build();
+
+                // Any character can be replaced by a character or have its case
+                // changed. Any other class in string2 has to hold every
+                // character it is paired with, which it leaves as it is.
+                let translatable = match (&pattern1, &pattern2) {
+                    (Pattern::Repeat(..), _) => false,
+                    (
+                        _,
+                        Pattern::Char(_)
+                        | Pattern::Cluster(_)
+                        | Pattern::Range(..)
+                        | Pattern::Equivalence(_)
+                        | Pattern::Repeat(..)
+                        | Pattern::Lower
+                        | Pattern::Upper,
+                    ) => true,
+                    // `e` is a hexadecimal digit as well as a letter
+                    (
+                        Pattern::Char('a') | Pattern::Range('a', 'c') | Pattern::Cluster(_),
+                        class,
+                    ) => matches!(
+                        class,
+                        Pattern::Alnum
+                            | Pattern::Alpha
+                            | Pattern::Graph
+                            | Pattern::Print
+                            | Pattern::Rune
+                            | Pattern::Xdigit
+                    ),
+                    (class1, class2) => class1 == class2,
+                };
+
+                match (&pattern1, result) {
+                    (Pattern::Repeat(..), result) => {
+                        assert!(matches!(result, Err(Error::MisplacedRepeat { .. })))
+                    }
+                    (_, Ok(_)) => assert!(translatable, "{} into {}", pattern1, pattern2),
+                    (_, Err(e)) => {
+                        assert!(!translatable, "{} into {}: {}", pattern1, pattern2, e);
+                        assert!(matches!(e, Error::Untranslatable { .. }));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn translations_follow_the_conformance_table() {
+        // string1, string2, input, output
+        let table = [
+            ("a", "x", "abc", "xbc"),
+            ("a-c", "x-z", "abcd", "xyzd"),
+            ("a-c", "[x*2]y", "abcd", "xxyd"),
+            ("abc", "[x*]y", "abcd", "xxyd"),
+            ("a", "[:alpha:]", "ab", "ab"),
+            ("a-c", "[:upper:]", "abcd", "ABCd"),
+            ("[=e=]", "x", "eéèf", "xxxf"),
+            ("[=e=]o", "[=a=]u", "éeo", "aau"),
+            ("[:alnum:]", "x", "a1 b", "xx x"),
+            ("[:alpha:]", "[:upper:]", "aé1", "AÉ1"),
+            ("[:blank:]", "_", "a b\tc\n", "a_b_c\n"),
+            ("[:cntrl:]", "?", "a\u{1}", "a?"),
+            ("[:digit:]", "a-j", "0129", "abcj"),
+            ("[:digit:]", "ab", "0129", "abbb"),
+            ("[:graph:]", "[:graph:]", "a! b", "a! b"),
+            ("[:ideogram:]", "?", "中a", "?a"),
+            ("[:lower:]", "A-C", "abcz", "ABCC"),
+            ("[:lower:]", "[:lower:]", "aB", "aB"),
+            ("[:phonogram:]", "?", "かa", "?a"),
+            ("[:print:]", "_", "a b\n", "___\n"),
+            ("[:punct:]", "_", "a,b!", "a_b_"),
+            ("[:rune:]", "x", "aé", "xx"),
+            ("[:space:]", "_", "a b\n", "a_b_"),
+            ("[:special:]", "_", "¿a?", "_a?"),
+            ("[:upper:]", "[:lower:]", "AÉ1", "aé1"),
+            ("[:xdigit:]", "[:upper:]", "afg", "AFg"),
+            ("e\u{301}", "x", "e\u{301}e", "xe"),
+            ("e\u{301}", "[:upper:]", "e\u{301}e", "E\u{301}e"),
+            ("ab", "e\u{301}", "abc", "e\u{301}e\u{301}c"),
+        ];
+
+        for (string1, string2, input, output) in table {
+            let translator = Translator::builder(string1)
+                .string2(string2)
+                .graphemes(true)
+                .build()
+                .unwrap();
+
+            assert_eq!(
+                translator.apply(input),
+                output,
+                "{} into {}",
+                string1,
+                string2
+            );
+        }
+    }
+
+    #[test]
+    fn complement_pairs_with_string2_in_code_point_order() {
+        let translator = Translator::builder("b-z")
+            .complement(true)
+            .string2("xy")
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("\0\u{1}ab"), "xyyb");
+    }
+
+    #[test]
+    fn collated_complement_pairs_with_string2_in_collation_sequence() {
+        let translator = Translator::builder("\\0-@B-`b-\\u{10ffff}")
+            .complement(true)
+            .collate(true)
+            .string2("xy")
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("Aa"), "yx");
+    }
+
+    #[test]
+    fn translating_complement_into_a_class_returns_error() {
+        let result = Translator::builder("a")
+            .complement(true)
+            .string2("[:alpha:]")
+            .build();
+
+        assert!(matches!(result, Err(Error::Untranslatable { .. })));
+    }
+
+    #[test]
+    fn class_meets_class_after_pairing_with_characters() {
+        let translator = Translator::builder("[:digit:]")
+            .string2("ab[:digit:]")
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("0123"), "ab23");
+    }
+
+    // ************************************************************************
+    // translate tests (case mapping)
+    // ************************************************************************
+
+    #[test]
+    fn sharp_s_becomes_two_capital_letters() {
+        let line = "straße".to_string();
+
+        let mut args = Cli {
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "STRASSE\n".as_bytes());
+    }
+
+    #[test]
+    fn ligature_becomes_two_capital_letters() {
+        let line = "ﬁx".to_string();
+
+        let mut args = Cli {
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "FIX\n".as_bytes());
+    }
+
+    #[test]
+    fn n_preceded_by_apostrophe_becomes_two_characters() {
+        let line = "ŉ".to_string();
+
+        let mut args = Cli {
+            string1: "[:alpha:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "\u{2BC}N\n".as_bytes());
+    }
+
+    #[test]
+    fn simple_case_mapping_keeps_the_length() {
+        let line = "straße ﬁx".to_string();
+
+        let mut args = Cli {
+            case_mapping: CaseMapping::Simple,
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "STRAßE ﬁX\n".as_bytes());
+    }
+
+    #[test]
+    fn simple_case_mapping_uses_titlecase_for_subscript_iota() {
+        let line = "ᾳ".to_string();
+
+        let mut args = Cli {
+            case_mapping: CaseMapping::Simple,
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "ᾼ\n".as_bytes());
+    }
+
+    #[test]
+    fn sigma_at_the_end_of_a_word_is_final() {
+        let line = "ΟΔΟΣ ΣΑΣ".to_string();
+
+        let mut args = Cli {
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "οδος σας\n".as_bytes());
+    }
+
+    #[test]
+    fn sigma_on_its_own_is_not_final() {
+        let line = "Σ".to_string();
+
+        let mut args = Cli {
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "σ\n".as_bytes());
+    }
+
+    #[test]
+    fn sigma_followed_by_an_accent_and_a_letter_is_not_final() {
+        let line = "ΑΣ\u{301}Α".to_string();
+
+        let mut args = Cli {
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "ασ\u{301}α\n".as_bytes());
+    }
+
+    #[test]
+    fn sigma_followed_by_an_accent_at_the_end_is_final() {
+        let line = "ΑΣ\u{301}".to_string();
+
+        let mut args = Cli {
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "ας\u{301}\n".as_bytes());
+    }
+
+    #[test]
+    fn simple_case_mapping_has_no_final_sigma() {
+        let line = "ΟΔΟΣ".to_string();
+
+        let mut args = Cli {
+            case_mapping: CaseMapping::Simple,
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "οδοσ\n".as_bytes());
+    }
+
+    #[test]
+    fn sigma_translated_into_a_character_is_not_changed() {
+        let line = "ΟΔΟΣ".to_string();
+
+        let mut args = Cli {
+            string1: "Σ".to_string(),
+            string2: Some("σ".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "ΟΔΟσ\n".as_bytes());
+    }
+
+    #[test]
+    fn squeeze_applies_to_each_character_of_a_full_mapping() {
+        let line = "ßs".to_string();
+
+        let mut args = Cli {
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::TranslateCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "S\n".as_bytes());
+    }
+
+    #[test]
+    fn turkish_capital_i_becomes_dotless_i() {
+        let line = "DİYARBAKIR".to_string();
+
+        let mut args = Cli {
+            locale: Some("tr_TR.UTF-8".to_string()),
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "diyarbakır\n".as_bytes());
+    }
+
+    #[test]
+    fn turkish_i_becomes_capital_dotted_i() {
+        let line = "istanbul ılık".to_string();
+
+        let mut args = Cli {
+            locale: Some("tr_TR.UTF-8".to_string()),
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "İSTANBUL ILIK\n".as_bytes());
+    }
+
+    #[test]
+    fn turkish_capital_i_with_a_dot_above_becomes_i() {
+        let line = "I\u{307} I".to_string();
+
+        let mut args = Cli {
+            locale: Some("tr".to_string()),
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "i \u{131}\n".as_bytes());
+    }
+
+    #[test]
+    fn azeri_uses_the_turkish_case_rules() {
+        let line = "BAKI".to_string();
+
+        let mut args = Cli {
+            locale: Some("az_AZ".to_string()),
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "bakı\n".as_bytes());
+    }
+
+    #[test]
+    fn lithuanian_keeps_the_dot_on_an_accented_i() {
+        let line = "ÌÍĨ".to_string();
+
+        let mut args = Cli {
+            locale: Some("lt_LT".to_string()),
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            writer,
+            "i\u{307}\u{300}i\u{307}\u{301}i\u{307}\u{303}\n".as_bytes()
+        );
+    }
+
+    #[test]
+    fn lithuanian_keeps_the_dot_on_a_letter_followed_by_an_accent() {
+        let line = "J\u{303} \u{12E}\u{300} I".to_string();
+
+        let mut args = Cli {
+            locale: Some("lt".to_string()),
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            writer,
+            "j\u{307}\u{303} \u{12F}\u{307}\u{300} i\n".as_bytes()
+        );
+    }
+
+    #[test]
+    fn lithuanian_uppercase_drops_the_dot_of_a_soft_dotted_letter() {
+        let line = "i\u{307}\u{301}x j\u{301}\u{307}".to_string();
+
+        let mut args = Cli {
+            locale: Some("lt".to_string()),
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        // a dot after another accent above is not the dot of the letter
+        assert!(result.is_ok());
+        assert_eq!(writer, "I\u{301}X J\u{301}\u{307}\n".as_bytes());
+    }
+
+    #[test]
+    fn lithuanian_dot_is_dropped_across_chunks_and_clusters() {
+        let mut args = Cli {
+            locale: Some("lt".to_string()),
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"i", "\u{307}".as_bytes()], &args, &Mode::Translate);
+        assert_eq!(output.unwrap(), b"I");
+
+        args.graphemes = true;
+        let mut writer = Vec::new();
+        let result = process_line(
+            "i\u{307}".to_string(),
+            &mut args,
+            &Mode::Translate,
+            &mut writer,
+        );
+        assert!(result.is_ok());
+        assert_eq!(writer, b"I\n");
+    }
+
+    #[test]
+    fn lithuanian_simple_case_mapping_drops_the_dot() {
+        let line = "ÌÍĨ".to_string();
+
+        let mut args = Cli {
+            locale: Some("lt".to_string()),
+            case_mapping: CaseMapping::Simple,
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "ìíĩ\n".as_bytes());
+    }
+
+    #[test]
+    fn other_locales_use_the_default_case_rules() {
+        let line = "DIYARBAKIR".to_string();
+
+        let mut args = Cli {
+            locale: Some("en_GB.UTF-8".to_string()),
+            string1: "[:upper:]".to_string(),
+            string2: Some("[:lower:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "diyarbakir\n".as_bytes());
+    }
+
+    // ************************************************************************
+    // translate tests (ranges)
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_lowercase_range_to_uppercase_range() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"CODING CHALLENGE\n");
+    }
+
+    #[test]
+    fn can_translate_part_of_a_range() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "a-e".to_string(),
+            string2: Some("1-5".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"3o4ing 3h1ll5ng5\n");
+    }
+
+    #[test]
+    fn can_translate_range_mixed_with_characters() {
+        let line = "coding-challenge".to_string();
+
+        let mut args = Cli {
+            string1: "-a-c".to_string(),
+            string2: Some("_A-C".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coding_ChAllenge\n");
+    }
+
+    #[test]
+    fn dash_at_the_end_of_a_string_is_a_literal() {
+        let line = "a-b".to_string();
+
+        let mut args = Cli {
+            string1: "a-".to_string(),
+            string2: Some("x_".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"x_b\n");
+    }
+
+    #[test]
+    fn can_translate_unicode_ranges() {
+        let line = "αβγ abc".to_string();
+
+        let mut args = Cli {
+            string1: "α-γ".to_string(),
+            string2: Some("a-c".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"abc abc\n");
+    }
+
+    #[test]
+    fn reversed_range_returns_error() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "z-a".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_err());
+        assert_eq!(
+            format!("{}", result.unwrap_err()),
+            "Invalid range `z-a`. The end of a range must not come before its start."
+        );
+    }
+
+    // ************************************************************************
+    // translate tests (escape sequences)
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_escaped_tab() {
+        let line = "coding\tchallenge".to_string();
+
+        let mut args = Cli {
+            string1: "\\t".to_string(),
+            string2: Some(" ".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"coding challenge\n");
+    }
+
+    #[test]
+    fn can_translate_to_escaped_backslash() {
+        let line = "coding/challenge".to_string();
+
+        let mut args = Cli {
+            string1: "/".to_string(),
+            string2: Some("\\\\".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"coding\\challenge\n");
+    }
+
+    #[test]
+    fn can_translate_octal_hex_and_unicode_escapes() {
+        let line = "abc".to_string();
+
+        let mut args = Cli {
+            string1: "\\141\\x62\\u{63}".to_string(),
+            string2: Some("\\101\\x42\\u{3b3}".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "ABγ\n".as_bytes());
+    }
+
+    #[test]
+    fn can_use_escapes_in_ranges() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "\\141-\\x7A".to_string(),
+            string2: Some("A-\\u{5A}".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"CODING CHALLENGE\n");
+    }
+
+    #[test]
+    fn escaped_dash_is_not_a_range() {
+        let line = "a-b-c".to_string();
+
+        let mut args = Cli {
+            string1: "a\\-c".to_string(),
+            string2: Some("x_z".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"x_b_z\n");
+    }
+
+    // ************************************************************************
+    // translate tests (repeated characters)
+    // ************************************************************************
+
+    #[test]
+    fn can_fill_string2_with_a_repeat() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("[x*]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"xxxxxx xxxxxxxxx\n");
+    }
+
+    #[test]
+    fn can_repeat_a_character_a_fixed_number_of_times() {
+        let line = "abcdef".to_string();
+
+        let mut args = Cli {
+            string1: "a-f".to_string(),
+            string2: Some("[x*3]yz".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"xxxyzz\n");
+    }
+
+    #[test]
+    fn repeat_count_starting_with_zero_is_octal() {
+        let line = "abcdefghij".to_string();
+
+        let mut args = Cli {
+            string1: "a-j".to_string(),
+            string2: Some("[x*010]yz".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"xxxxxxxxyz\n");
+    }
+
+    #[test]
+    fn can_follow_a_repeat_with_a_range() {
+        let line = "abcdef".to_string();
+
+        let mut args = Cli {
+            string1: "a-f".to_string(),
+            string2: Some("[_*3]A-C".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"___ABC\n");
+    }
+
+    #[test]
+    fn can_fill_before_the_end_of_string2() {
+        let line = "abcdef".to_string();
+
+        let mut args = Cli {
+            string1: "a-f".to_string(),
+            string2: Some("x[-*]z".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"x----z\n");
+    }
+
+    #[test]
+    fn huge_repeat_counts_do_not_overflow() {
+        for (complement1, complement2, expected) in [
+            (false, false, "yyydef\n"),
+            (true, false, "abcyyy\n"),
+            (false, true, "abcyyy\n"),
+        ] {
+            let line = "abcdef".to_string();
+
+            let mut args = Cli {
+                complement1,
+                complement2,
+                string1: "a-c".to_string(),
+                string2: Some("[x*][y*18446744073709551615][z*5]".to_string()),
+                ..Default::default()
+            };
+
+            let mut writer = Vec::new();
+
+            let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+            assert!(result.is_ok());
+            assert_eq!(writer, expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn can_fill_a_complement_with_a_repeat() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "a-z".to_string(),
+            string2: Some("[-*]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"coding-challenge\n");
+    }
+
+    #[test]
+    fn bracket_without_repeat_is_a_character() {
+        let line = "a[b".to_string();
+
+        let mut args = Cli {
+            string1: "[".to_string(),
+            string2: Some("(".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"a(b\n");
+    }
+
+    #[test]
+    fn repeat_in_string1_returns_error() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[a*3]".to_string(),
+            string2: Some("x".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert_eq!(
+            format!("{}", result.unwrap_err()),
+            "The repeat `[a*3]` can only be used in string2."
+        );
+    }
+
+    #[test]
+    fn more_than_one_fill_returns_error() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("[x*][y*]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_err());
+    }
+
+    // ************************************************************************
+    // translate tests (equivalence classes)
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_an_equivalence_class() {
+        let line = "élève été naïve".to_string();
+
+        let mut args = Cli {
+            string1: "[=e=]".to_string(),
+            string2: Some("e".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "eleve ete naïve\n".as_bytes());
+    }
+
+    #[test]
+    fn can_translate_many_equivalence_classes() {
+        let line = "Renée Müller".to_string();
+
+        let mut args = Cli {
+            string1: "[=e=][=u=]".to_string(),
+            string2: Some("eu".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Renee Muller\n");
+    }
+
+    #[test]
+    fn equivalence_class_can_hold_an_accented_character() {
+        let line = "élève".to_string();
+
+        let mut args = Cli {
+            string1: "[=é=]".to_string(),
+            string2: Some("_".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"_l_v_\n");
+    }
+
+    #[test]
+    fn equivalence_class_does_not_match_other_cases() {
+        let line = "Éé".to_string();
+
+        let mut args = Cli {
+            string1: "[=e=]".to_string(),
+            string2: Some("x".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "Éx\n".as_bytes());
+    }
+
+    #[test]
+    fn equivalence_class_in_string2_is_its_base_letter() {
+        let line = "abc".to_string();
+
+        let mut args = Cli {
+            string1: "b".to_string(),
+            string2: Some("[=é=]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"aec\n");
+    }
+
+    #[test]
+    fn unfinished_equivalence_class_is_a_literal() {
+        let line = "[=e".to_string();
+
+        let mut args = Cli {
+            string1: "[=e".to_string(),
+            string2: Some("123".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"123\n");
+    }
+
+    // ************************************************************************
+    // grapheme mode tests
+    // ************************************************************************
+
+    #[test]
+    fn can_delete_a_cluster_in_grapheme_mode() {
+        let line = "cafe\u{301} cafe".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "e\\u{301}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"caf cafe\n");
+    }
+
+    #[test]
+    fn character_does_not_match_a_cluster_it_starts() {
+        let line = "cafe\u{301} cafe".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "e".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "cafe\u{301} caf\n".as_bytes());
+    }
+
+    #[test]
+    fn can_translate_a_flag_emoji() {
+        let line = "🇫🇷 🇩🇪".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "🇫🇷🇩🇪".to_string(),
+            string2: Some("FD".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"F D\n");
+    }
+
+    #[test]
+    fn can_translate_a_character_into_a_cluster() {
+        let line = "family: f".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "f".to_string(),
+            string2: Some("👨‍👩‍👧".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "👨‍👩‍👧amily: 👨‍👩‍👧\n".as_bytes());
+    }
+
+    #[test]
+    fn class_matches_a_cluster_by_its_first_character() {
+        let line = "cafe\u{301}".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "CAFE\u{301}\n".as_bytes());
+    }
+
+    #[test]
+    fn class_members_paired_by_position_translate_clusters() {
+        let line = "1\u{20E3} 2".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "[:digit:]".to_string(),
+            string2: Some("a-j".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"b c\n");
+    }
+
+    #[test]
+    fn can_squeeze_repeated_clusters() {
+        let line = "🇫🇷🇫🇷🇫🇷 e\u{301}e\u{301}".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            string1: "🇫🇷e\\u{301}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "🇫🇷 e\u{301}\n".as_bytes());
+    }
+
+    #[test]
+    fn complement_leaves_out_the_clusters_in_string1() {
+        let line = "cafe\u{301}".to_string();
+
+        let mut args = Cli {
+            graphemes: true,
+            complement1: true,
+            string1: "e\\u{301}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "e\u{301}\n".as_bytes());
+    }
+
+    #[test]
+    fn cluster_split_between_chunks_is_read_as_one() {
+        let args = Cli {
+            graphemes: true,
+            string1: "e\\u{301}".to_string(),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"cafe", "\u{301}!".as_bytes()], &args, &Mode::Delete);
+
+        assert_eq!(output.unwrap(), b"caf!");
+    }
+
+    #[test]
+    fn characters_are_not_grouped_outside_grapheme_mode() {
+        let line = "cafe\u{301}".to_string();
+
+        let mut args = Cli {
+            string1: "e\\u{301}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"caf\n");
+    }
+
+    // ************************************************************************
+    // normalization tests
+    // ************************************************************************
+
+    #[test]
+    fn normalized_input_matches_both_ways_of_writing_an_accent() {
+        let line = "cafe\u{301} café".to_string();
+
+        let mut args = Cli {
+            normalize: Some(Normalization::Nfc),
+            string1: "é".to_string(),
+            string2: Some("E".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"cafE cafE\n");
+    }
+
+    #[test]
+    fn sets_are_normalized_like_the_input() {
+        let line = "café".to_string();
+
+        let mut args = Cli {
+            normalize: Some(Normalization::Nfc),
+            string1: "e\\u{301}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"caf\n");
+    }
+
+    #[test]
+    fn can_delete_accents_after_decomposing_the_input() {
+        let line = "crème brûlée".to_string();
+
+        let mut args = Cli {
+            normalize: Some(Normalization::Nfd),
+            string1: "\\u{300}-\\u{36F}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"creme brulee\n");
+    }
+
+    #[test]
+    fn compatibility_normalization_splits_ligatures() {
+        let line = "ﬁne".to_string();
+
+        let mut args = Cli {
+            normalize: Some(Normalization::Nfkc),
+            string1: "fi".to_string(),
+            string2: Some("FI".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"FIne\n");
+    }
+
+    #[test]
+    fn can_normalize_the_output() {
+        let line = "e\u{301}".to_string();
+
+        let mut args = Cli {
+            normalize_output: Some(Normalization::Nfc),
+            string1: "e".to_string(),
+            string2: Some("a".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "á\n".as_bytes());
+    }
+
+    #[test]
+    fn accent_split_between_chunks_is_normalized() {
+        let args = Cli {
+            normalize: Some(Normalization::Nfc),
+            string1: "é".to_string(),
+            string2: Some("E".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"cafe", "\u{301}!".as_bytes()], &args, &Mode::Translate);
+
+        assert_eq!(output.unwrap(), b"cafE!");
+    }
+
+    #[test]
+    fn deleting_a_decomposed_character_leaves_its_base_letter_alone() {
+        let line = "e é cafe\u{301}".to_string();
+
+        let mut args = Cli {
+            normalize: Some(Normalization::Nfd),
+            string1: "é".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"e  caf\n");
+    }
+
+    #[test]
+    fn decomposed_set_character_takes_one_position() {
+        let line = "é a".to_string();
+
+        let mut args = Cli {
+            normalize: Some(Normalization::Nfd),
+            string1: "éa".to_string(),
+            string2: Some("xy".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"x y\n");
+    }
+
+    // ************************************************************************
+    // transliteration tests
+    // ************************************************************************
+
+    #[test]
+    fn can_transliterate_every_character() {
+        let line = "Æsir straße 5€ Łódź".to_string();
+
+        let mut args = Cli {
+            ascii: true,
+            string1: "[:rune:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Transliterate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"AEsir strasse 5EUR Lodz\n");
+    }
+
+    #[test]
+    fn only_characters_in_string1_are_transliterated() {
+        let line = "àéü".to_string();
+
+        let mut args = Cli {
+            ascii: true,
+            string1: "é".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Transliterate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "àeü\n".as_bytes());
+    }
+
+    #[test]
+    fn transliteration_falls_back_to_a_question_mark() {
+        let line = "né 中".to_string();
+
+        let mut args = Cli {
+            ascii: true,
+            string1: "[:rune:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Transliterate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"ne ?\n");
+    }
+
+    #[test]
+    fn transliteration_can_drop_or_keep_what_it_cannot_spell() {
+        for (fallback, expected) in [
+            (AsciiFallback::Drop, "ne \n"),
+            (AsciiFallback::Keep, "ne 中\n"),
+        ] {
+            let mut args = Cli {
+                ascii: true,
+                ascii_fallback: fallback,
+                string1: "[:rune:]".to_string(),
+                ..Default::default()
+            };
+
+            let mut writer = Vec::new();
+
+            let result = process_line(
+                "né 中".to_string(),
+                &mut args,
+                &Mode::Transliterate,
+                &mut writer,
+            );
+
+            assert!(result.is_ok());
+            assert_eq!(writer, expected.as_bytes(), "{:?}", fallback);
+        }
+    }
+
+    #[test]
+    fn transliteration_comes_before_translating_and_squeezing() {
+        let line = "Crème Brûlée, à la carte!".to_string();
+
+        let mut args = Cli {
+            ascii: true,
+            complement1: true,
+            squeeze: true,
+            string1: "a-zA-Z".to_string(),
+            string2: Some("-".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::TranslateCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Creme-Brulee-a-la-carte-\n");
+    }
+
+    #[test]
+    fn transliteration_comes_before_deleting() {
+        let line = "naïve".to_string();
+
+        let mut args = Cli {
+            ascii: true,
+            delete: true,
+            string1: "i".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"nave\n");
+    }
+
+    #[test]
+    fn transliteration_drops_decomposed_accents() {
+        let line = "cafe\u{301}".to_string();
+
+        let mut args = Cli {
+            ascii: true,
+            graphemes: true,
+            string1: "[:rune:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Transliterate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"cafe\n");
+    }
+
+    #[test]
+    fn can_transliterate_latin_1_bytes() {
+        let mut args = Cli {
+            ascii: true,
+            bytes: true,
+            string1: "[:rune:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(
+            b"caf\xe9 \xdf",
+            &mut args,
+            &Mode::Transliterate,
+            &mut writer,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"cafe ss\n");
+    }
+
+    // ************************************************************************
+    // byte mode and invalid UTF-8 tests
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_latin1_bytes_in_byte_mode() {
+        let line = b"caf\xe9";
+
+        let mut args = Cli {
+            bytes: true,
+            string1: "\\351".to_string(),
+            string2: Some("e".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"cafe\n");
+    }
+
+    #[test]
+    fn only_ascii_letters_change_case_in_byte_mode() {
+        let line = b"caf\xe9";
+
+        let mut args = Cli {
+            bytes: true,
+            string1: "[:lower:]\\351".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"CAF\xe9\n");
+    }
+
+    #[test]
+    fn classes_only_match_ascii_in_byte_mode() {
+        let line = b"caf\xe9";
+
+        let mut args = Cli {
+            bytes: true,
+            string1: "[:lower:]".to_string(),
+            string2: Some("[:upper:]".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"CAF\xe9\n");
+    }
+
+    #[test]
+    fn can_delete_a_range_of_bytes() {
+        let line = b"a\xffb\x80c";
+
+        let mut args = Cli {
+            bytes: true,
+            string1: "\\200-\\377".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"abc\n");
+    }
+
+    #[test]
+    fn can_squeeze_bytes() {
+        let line = b"a\xff\xff\xffb";
+
+        let mut args = Cli {
+            bytes: true,
+            string1: "\\377".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"a\xffb\n");
+    }
+
+    #[test]
+    fn can_translate_the_complement_of_bytes() {
+        let line = b"ab\xfe\xff";
+
+        let mut args = Cli {
+            bytes: true,
+            complement1: true,
+            string1: "a-z".to_string(),
+            string2: Some("_".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"ab__\n");
+    }
+
+    #[test]
+    fn can_pass_invalid_utf8_through() {
+        let line = b"ab\xffc\xe9";
+
+        let mut args = Cli {
+            invalid_utf8: InvalidUtf8::Pass,
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"AB\xffC\xe9\n");
+    }
+
+    #[test]
+    fn invalid_utf8_returns_error_with_byte_offset() {
+        let args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let translator = translator(&args, &Mode::Translate).unwrap();
+        let mut stream = Stream::default();
+        let mut writer = Vec::new();
+
+        assert!(stream
+            .write(&translator, b"coding\nchallenge\n", &mut writer)
+            .is_ok());
+        let result = stream.write(&translator, b"ab\xffc", &mut writer);
+
+        assert_eq!(
+            format!("{}", result.unwrap_err()),
+            "Invalid UTF-8 at byte offset 19."
+        );
+    }
+
+    #[test]
+    fn character_above_a_byte_returns_error_in_byte_mode() {
+        let mut args = Cli {
+            bytes: true,
+            string1: "\\u{20ac}".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(b"10\xe2\x82\xac", &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn characters_written_as_themselves_are_their_utf8_bytes_in_byte_mode() {
+        let mut args = Cli {
+            bytes: true,
+            string1: "é€".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_bytes(
+            b"caf\xc3\xa9 10\xe2\x82\xac \xe9",
+            &mut args,
+            &Mode::Delete,
+            &mut writer,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"caf 10 \xe9\n");
+    }
+
+    // ************************************************************************
+    // stream tests
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_newlines() {
+        let args = Cli {
+            string1: "\\n".to_string(),
+            string2: Some(" ".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"coding\nchallenge\n"], &args, &Mode::Translate);
+
+        assert_eq!(output.unwrap(), b"coding challenge ");
+    }
+
+    #[test]
+    fn can_delete_newlines() {
+        let args = Cli {
+            string1: "\\n".to_string(),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"coding\nchallenge\n"], &args, &Mode::Delete);
+
+        assert_eq!(output.unwrap(), b"codingchallenge");
+    }
+
+    #[test]
+    fn can_squeeze_blank_lines() {
+        let args = Cli {
+            string1: "\\n".to_string(),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"coding\n\n\nchallenge\n"], &args, &Mode::Compress);
+
+        assert_eq!(output.unwrap(), b"coding\nchallenge\n");
+    }
+
+    #[test]
+    fn input_without_a_trailing_newline_is_unchanged() {
+        let args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"coding challenge"], &args, &Mode::Translate);
+
+        assert_eq!(output.unwrap(), b"CODING CHALLENGE");
+    }
+
+    #[test]
+    fn carriage_returns_are_kept() {
+        let args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"coding\r\nchallenge\r\n"], &args, &Mode::Translate);
+
+        assert_eq!(output.unwrap(), b"CODING\r\nCHALLENGE\r\n");
+    }
+
+    #[test]
+    fn squeezing_carries_on_across_chunks() {
+        let args = Cli {
+            string1: "o".to_string(),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"coo", b"oding"], &args, &Mode::Compress);
+
+        assert_eq!(output.unwrap(), b"coding");
+    }
+
+    #[test]
+    fn translating_and_squeezing_carries_on_across_chunks() {
+        let args = Cli {
+            string1: "d".to_string(),
+            string2: Some("o".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"cod", b"ding"], &args, &Mode::TranslateCompress);
+
+        assert_eq!(output.unwrap(), b"coing");
+    }
+
+    #[test]
+    fn characters_can_be_split_across_chunks() {
+        let args = Cli {
+            string1: "[=e=]".to_string(),
+            string2: Some("x".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"caf\xc3", b"\xa9"], &args, &Mode::Translate);
+
+        assert_eq!(output.unwrap(), b"cafx");
+    }
+
+    #[test]
+    fn can_pass_a_character_cut_off_at_the_end_through() {
+        let args = Cli {
+            invalid_utf8: InvalidUtf8::Pass,
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"caf\xc3"], &args, &Mode::Translate);
+
+        assert_eq!(output.unwrap(), b"CAF\xc3");
+    }
+
+    #[test]
+    fn character_cut_off_at_the_end_returns_error() {
+        let args = Cli {
+            string1: "a-z".to_string(),
+            string2: Some("A-Z".to_string()),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"caf", b"\xc3"], &args, &Mode::Translate);
+
+        assert_eq!(
+            format!("{}", output.unwrap_err()),
+            "Invalid UTF-8 at byte offset 3."
+        );
+    }
+
+    // ************************************************************************
+    // delete tests
+    // ************************************************************************
+    #[test]
+    fn can_delete_single_characters() {
+        let line = "Coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "C".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"oding challenge\n");
+    }
+
+    #[test]
+    fn can_delete_many_characters() {
+        let line = "Coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "Cdg".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"oin challene\n");
+    }
+
+    #[test]
+    fn can_delete_nothing() {
+        let line = "Coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coding challenge\n");
+    }
+
+    #[test]
+    fn can_delete_everything() {
+        let line = "Coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "Coding challenge".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"\n");
+    }
+
+    #[test]
+    fn can_delete_digits() {
+        let line = "123 challenge".to_string();
+
+        let mut args = Cli {
+            string1: "123".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b" challenge\n");
+    }
+
+    #[test]
+    fn can_delete_whitespace() {
+        let line = "Coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: " ".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Codingchallenge\n");
+    }
+
+    #[test]
+    fn can_delete_special_chars() {
+        let line = "Coding@challenge".to_string();
+
+        let mut args = Cli {
+            string1: "@".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Codingchallenge\n");
+    }
+
+    #[test]
+    fn can_delete_upper_class() {
+        let line = "CoDinG challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:upper:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
         assert_eq!(writer, b"oin challenge\n");
     }
 
     #[test]
-    fn can_delete_lower_class() {
-        let line = "CoDinG challenge".to_string();
+    fn can_delete_lower_class() {
+        let line = "CoDinG challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:lower:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"CDG \n");
+    }
+
+    #[test]
+    fn can_delete_alphabetic_chars() {
+        let line = "123 challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:alpha:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"123 \n");
+    }
+
+    #[test]
+    fn can_delete_alphanumeric_chars() {
+        let line = "123@challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:alnum:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"@\n");
+    }
+
+    #[test]
+    fn can_delete_blanks() {
+        let line = "123 challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:blank:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"123challenge\n");
+    }
+
+    #[test]
+    fn can_delete_control_characters() {
+        let line = "123\tchallenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:cntrl:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"123challenge\n");
+    }
+
+    #[test]
+    fn can_delete_digit_characters() {
+        let line = "٣7৬¾ challenge".to_string();
+
+        let mut args = Cli {
+            string1: "[:digit:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b" challenge\n");
+    }
+
+    #[test]
+    fn can_delete_a_range() {
+        let line = "Coding challenge 2024".to_string();
+
+        let mut args = Cli {
+            string1: "a-h0-9".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coin lln \n");
+    }
+
+    #[test]
+    fn can_delete_punctuation_class() {
+        let line = "Coding, challenge! $5 ¿qué?".to_string();
+
+        let mut args = Cli {
+            string1: "[:punct:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "Coding challenge 5 qué\n".as_bytes());
+    }
+
+    #[test]
+    fn punctuation_class_leaves_marks_and_format_characters_alone() {
+        let line = "cafe\u{301}, a\u{200B}b!".to_string();
+
+        let mut args = Cli {
+            string1: "[:punct:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "cafe\u{301} a\u{200B}b\n".as_bytes());
+    }
+
+    #[test]
+    fn graph_class_only_matches_assigned_visible_characters() {
+        let line = "e\u{301}\u{200B}\u{378}\u{A0}".to_string();
+
+        let mut args = Cli {
+            string1: "[:graph:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "\u{200B}\u{378}\u{A0}\n".as_bytes());
+    }
+
+    #[test]
+    fn print_class_adds_spaces_to_the_graph_class() {
+        let line = "é\u{A0}\u{200B}\u{378}".to_string();
+
+        let mut args = Cli {
+            string1: "[:print:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "\u{200B}\u{378}\n".as_bytes());
+    }
+
+    #[test]
+    fn can_delete_graph_class() {
+        let line = "Coding challenge!\t".to_string();
+
+        let mut args = Cli {
+            string1: "[:graph:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b" \t\n");
+    }
+
+    #[test]
+    fn can_delete_unprintable_characters() {
+        let line = "Coding\u{1}\u{7f} challenge\u{85}".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "[:print:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coding challenge\n");
+    }
+
+    #[test]
+    fn can_delete_hex_digits() {
+        let line = "0xFF coding 0x1e".to_string();
+
+        let mut args = Cli {
+            string1: "[:xdigit:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"x oing x\n");
+    }
+
+    #[test]
+    fn can_delete_ideogram_class() {
+        let line = "漢字とカタカナ abc".to_string();
+
+        let mut args = Cli {
+            string1: "[:ideogram:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "とカタカナ abc\n".as_bytes());
+    }
+
+    #[test]
+    fn can_delete_phonogram_class() {
+        let line = "漢字とカタカナ 한국어".to_string();
+
+        let mut args = Cli {
+            string1: "[:phonogram:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "漢字 \n".as_bytes());
+    }
+
+    #[test]
+    fn can_delete_rune_class() {
+        let line = "漢字 abc ¿?".to_string();
+
+        let mut args = Cli {
+            string1: "[:rune:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"\n");
+    }
+
+    #[test]
+    fn can_delete_special_class() {
+        let line = "¿qué? 5€ →".to_string();
+
+        let mut args = Cli {
+            string1: "[:special:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "qué? 5 \n".as_bytes());
+    }
+
+    #[test]
+    fn special_class_leaves_combining_accents_alone() {
+        let line = "cafe\u{301} «ok»".to_string();
+
+        let mut args = Cli {
+            string1: "[:special:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "cafe\u{301} ok\n".as_bytes());
+    }
+
+    #[test]
+    fn can_keep_only_cjk_characters() {
+        let line = "漢字とカタカナ abc".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "[:ideogram:][:phonogram:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "漢字とカタカナ\n".as_bytes());
+    }
+
+    #[test]
+    fn can_delete_an_equivalence_class() {
+        let line = "élève été eee".to_string();
+
+        let mut args = Cli {
+            string1: "[=e=]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"lv t \n");
+    }
+
+    #[test]
+    fn can_delete_the_complement_of_an_equivalence_class() {
+        let line = "élève".to_string();
 
         let mut args = Cli {
-            string1: "[:lower:]".to_string(),
+            complement1: true,
+            string1: "[=e=]".to_string(),
             ..Default::default()
         };
 
@@ -1395,15 +3401,15 @@ mod tests {
         let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"CDG \n");
+        assert_eq!(writer, "éèe\n".as_bytes());
     }
 
     #[test]
-    fn can_delete_alphabetic_chars() {
-        let line = "123 challenge".to_string();
+    fn can_delete_escaped_characters() {
+        let line = "coding\r\tchallenge".to_string();
 
         let mut args = Cli {
-            string1: "[:alpha:]".to_string(),
+            string1: "\\r\\t".to_string(),
             ..Default::default()
         };
 
@@ -1412,15 +3418,15 @@ mod tests {
         let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"123 \n");
+        assert_eq!(writer, b"codingchallenge\n");
     }
 
     #[test]
-    fn can_delete_alphanumeric_chars() {
-        let line = "123@challenge".to_string();
+    fn can_delete_a_unicode_range() {
+        let line = "café naïve".to_string();
 
         let mut args = Cli {
-            string1: "[:alnum:]".to_string(),
+            string1: "à-ÿ".to_string(),
             ..Default::default()
         };
 
@@ -1429,15 +3435,15 @@ mod tests {
         let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"@\n");
+        assert_eq!(writer, b"caf nave\n");
     }
 
     #[test]
-    fn can_delete_blanks() {
-        let line = "123 challenge".to_string();
+    fn deleting_a_character_leaves_its_combining_marks_by_default() {
+        let line = "cafe\u{301}".to_string();
 
         let mut args = Cli {
-            string1: "[:blank:]".to_string(),
+            string1: "e".to_string(),
             ..Default::default()
         };
 
@@ -1446,15 +3452,16 @@ mod tests {
         let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"123challenge\n");
+        assert_eq!(writer, "caf\u{301}\n".as_bytes());
     }
 
     #[test]
-    fn can_delete_control_characters() {
-        let line = "123\tchallenge".to_string();
+    fn can_delete_the_combining_marks_of_a_deleted_character() {
+        let line = "cafe\u{301}\u{323} e\u{301}x \u{301}".to_string();
 
         let mut args = Cli {
-            string1: "[:cntrl:]".to_string(),
+            delete_marks: true,
+            string1: "e".to_string(),
             ..Default::default()
         };
 
@@ -1463,24 +3470,39 @@ mod tests {
         let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b"123challenge\n");
+        assert_eq!(writer, "caf x \u{301}\n".as_bytes());
     }
 
     #[test]
-    fn can_delete_digit_characters() {
-        let line = "٣7৬¾ challenge".to_string();
+    fn combining_marks_of_a_deleted_character_are_deleted_across_chunks() {
+        let args = Cli {
+            delete_marks: true,
+            string1: "e".to_string(),
+            ..Default::default()
+        };
+
+        let output = process_chunks(&[b"cafe", "\u{301}!".as_bytes()], &args, &Mode::Delete);
+
+        assert_eq!(output.unwrap(), b"caf!");
+    }
+
+    #[test]
+    fn combining_marks_of_a_deleted_character_are_not_squeezed_into_the_next() {
+        let line = "ae\u{301}a".to_string();
 
         let mut args = Cli {
-            string1: "[:digit:]".to_string(),
+            delete_marks: true,
+            string1: "e".to_string(),
+            string2: Some("a".to_string()),
             ..Default::default()
         };
 
         let mut writer = Vec::new();
 
-        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+        let result = process_line(line, &mut args, &Mode::DeleteCompress, &mut writer);
 
         assert!(result.is_ok());
-        assert_eq!(writer, b" challenge\n");
+        assert_eq!(writer, b"a\n");
     }
 
     // ************************************************************************
@@ -1639,6 +3661,168 @@ mod tests {
         assert_eq!(writer, b"Coding\tchallenge\n");
     }
 
+    #[test]
+    fn can_squeeze_punctuation_class() {
+        let line = "Coding,, challenge!!!".to_string();
+
+        let mut args = Cli {
+            string1: "[:punct:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coding, challenge!\n");
+    }
+
+    #[test]
+    fn can_squeeze_hex_digit_class() {
+        let line = "Coding 0xFFFF".to_string();
+
+        let mut args = Cli {
+            string1: "[:xdigit:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coding 0xF\n");
+    }
+
+    #[test]
+    fn can_squeeze_ideogram_class() {
+        let line = "字字字 abc".to_string();
+
+        let mut args = Cli {
+            string1: "[:ideogram:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "字 abc\n".as_bytes());
+    }
+
+    #[test]
+    fn can_squeeze_an_equivalence_class() {
+        let line = "ééé eee èe".to_string();
+
+        let mut args = Cli {
+            string1: "[=e=]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, "é e èe\n".as_bytes());
+    }
+
+    #[test]
+    fn can_squeeze_a_range() {
+        let line = "Coddding   challenge".to_string();
+
+        let mut args = Cli {
+            string1: "a-m".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coding   chalenge\n");
+    }
+
+    // ************************************************************************
+    // translate and compress tests (s flag)
+    // ************************************************************************
+
+    #[test]
+    fn can_translate_and_squeeze_single_characters() {
+        let line = "coding challenge".to_string();
+
+        let mut args = Cli {
+            string1: "ln".to_string(),
+            string2: Some("L".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::TranslateCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"codiLg chaLeLge\n");
+    }
+
+    #[test]
+    fn can_translate_a_range_and_squeeze_the_result() {
+        let line = "aabbcc xyz  q".to_string();
+
+        let mut args = Cli {
+            string1: "a-c".to_string(),
+            string2: Some("X".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::TranslateCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"X xyz  q\n");
+    }
+
+    #[test]
+    fn only_squeezes_characters_in_string2() {
+        let line = "aabb  cc".to_string();
+
+        let mut args = Cli {
+            string1: "a".to_string(),
+            string2: Some("b".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::TranslateCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"b  cc\n");
+    }
+
+    #[test]
+    fn can_translate_the_complement_and_squeeze() {
+        let line = "coding,,  challenge".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "a-z".to_string(),
+            string2: Some("_".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::TranslateCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"coding_challenge\n");
+    }
+
     // ************************************************************************
     // delete and compress tests (ds flags)
     // ************************************************************************
@@ -1736,4 +3920,136 @@ mod tests {
     // ************************************************************************
     // translate tests (Ccu flags)
     // ************************************************************************
+
+    #[test]
+    fn can_translate_the_complement_of_a_set() {
+        let line = "coding challenge 2024".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "a-z".to_string(),
+            string2: Some("_".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"coding_challenge_____\n");
+    }
+
+    #[test]
+    fn complement_maps_to_the_last_character_of_string2() {
+        let line = "coding challenge!".to_string();
+
+        let mut args = Cli {
+            complement2: true,
+            string1: "a-z".to_string(),
+            string2: Some("xyz".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"codingzchallengez\n");
+    }
+
+    #[test]
+    fn complement_is_paired_with_string2_in_code_point_order() {
+        let line = "\u{0}\u{1}\u{2}a".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "a".to_string(),
+            string2: Some("xyz".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"xyza\n");
+    }
+
+    #[test]
+    fn complement_is_paired_with_string2_in_collation_sequence_with_c() {
+        let line = "ÀBa!".to_string();
+
+        let mut args = Cli {
+            complement2: true,
+            string1: "\\0- \\\"-@C-`b-\\u{bf}\\u{c1}-\\u{10ffff}".to_string(),
+            string2: Some("xyzw".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Translate, &mut writer);
+
+        assert!(result.is_ok());
+        // The complement is `!`, `a`, `A`, `À` and `B` in collation sequence
+        assert_eq!(writer, b"wwyx\n");
+    }
+
+    #[test]
+    fn can_delete_the_complement_of_a_set() {
+        let line = "Coding, challenge #1!".to_string();
+
+        let mut args = Cli {
+            complement2: true,
+            string1: "[:alnum:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Delete, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Codingchallenge1\n");
+    }
+
+    #[test]
+    fn can_squeeze_the_complement_of_a_set() {
+        let line = "Coddding   challenge!!".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "[:alpha:]".to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::Compress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"Coddding challenge!\n");
+    }
+
+    #[test]
+    fn complement_only_applies_to_deleted_characters() {
+        let line = "Coding   challenge 2024".to_string();
+
+        let mut args = Cli {
+            complement1: true,
+            string1: "a-z ".to_string(),
+            string2: Some(" l".to_string()),
+            ..Default::default()
+        };
+
+        let mut writer = Vec::new();
+
+        let result = process_line(line, &mut args, &Mode::DeleteCompress, &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"oding chalenge \n");
+    }
 }
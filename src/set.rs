@@ -0,0 +1,1320 @@
+use crate::args::Normalization;
+use crate::error::{Error, Operand};
+use crate::tables::{in_ranges, GRAPH_RANGES, PUNCT_RANGES};
+
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Defines the patterns in string1 and string2 to process
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// Represents a character
+    Char(char),
+    /// Represents an extended grapheme cluster of more than one character, such
+    /// as an `e` followed by a combining accent or a flag emoji, in grapheme
+    /// mode
+    Cluster(String),
+    /// Represents every character from the first to the last (inclusive)
+    Range(char, char),
+    /// Represents every character that shares a base letter with the given
+    /// character once accents and other combining marks are removed
+    Equivalence(char),
+    /// Represents a character repeated a number of times, or enough times to
+    /// make string2 as long as string1 when there is no count
+    Repeat(char, Option<usize>),
+    Alnum,
+    Alpha,
+    Blank,
+    Cntrl,
+    Digit,
+    Graph,
+    Ideogram,
+    Lower,
+    Phonogram,
+    Print,
+    Punct,
+    Rune,
+    Space,
+    Special,
+    Upper,
+    Xdigit,
+}
+
+impl fmt::Display for Pattern {
+    /// Write the pattern the way it would appear in a set string
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Char(c) => write!(f, "{}", c.escape_debug()),
+            Pattern::Cluster(cluster) => write!(f, "{}", cluster.escape_debug()),
+            Pattern::Range(start, end) => {
+                write!(f, "{}-{}", start.escape_debug(), end.escape_debug())
+            }
+            Pattern::Equivalence(c) => write!(f, "[={}=]", c.escape_debug()),
+            Pattern::Repeat(c, Some(count)) => write!(f, "[{}*{}]", c.escape_debug(), count),
+            Pattern::Repeat(c, None) => write!(f, "[{}*]", c.escape_debug()),
+            Pattern::Alnum => write!(f, "[:alnum:]"),
+            Pattern::Alpha => write!(f, "[:alpha:]"),
+            Pattern::Blank => write!(f, "[:blank:]"),
+            Pattern::Cntrl => write!(f, "[:cntrl:]"),
+            Pattern::Digit => write!(f, "[:digit:]"),
+            Pattern::Graph => write!(f, "[:graph:]"),
+            Pattern::Ideogram => write!(f, "[:ideogram:]"),
+            Pattern::Lower => write!(f, "[:lower:]"),
+            Pattern::Phonogram => write!(f, "[:phonogram:]"),
+            Pattern::Print => write!(f, "[:print:]"),
+            Pattern::Punct => write!(f, "[:punct:]"),
+            Pattern::Rune => write!(f, "[:rune:]"),
+            Pattern::Space => write!(f, "[:space:]"),
+            Pattern::Special => write!(f, "[:special:]"),
+            Pattern::Upper => write!(f, "[:upper:]"),
+            Pattern::Xdigit => write!(f, "[:xdigit:]"),
+        }
+    }
+}
+
+/// Extract graphemes (characters), ranges and classes ready to translate a string
+/// by, each with the bytes of the set string it was written as
+pub(crate) fn parse(string: &str, operand: Operand) -> Result<Vec<(Pattern, Range<usize>)>, Error> {
+    let tokens = Lexer::new(string, operand).lex()?;
+
+    // start a vector to hold the patterns in
+    let mut patterns = Vec::new();
+
+    // loop through the tokens and parse each one into a Pattern type. A
+    // character followed by an unescaped dash and another character is a range.
+    let mut i = 0;
+    while i < tokens.len() {
+        let (token, span) = &tokens[i];
+        let start = match token {
+            Token::Class(name) => {
+                patterns.push((get_class(name, operand, span.start)?, span.clone()));
+                i += 1;
+                continue;
+            }
+            Token::Equivalence(c) => {
+                patterns.push((Pattern::Equivalence(*c), span.clone()));
+                i += 1;
+                continue;
+            }
+            Token::Repeat(c, count) => {
+                patterns.push((Pattern::Repeat(*c, *count), span.clone()));
+                i += 1;
+                continue;
+            }
+            Token::Literal(c) | Token::Escaped(c) => *c,
+        };
+
+        match (tokens.get(i + 1), tokens.get(i + 2)) {
+            (
+                Some((Token::Literal('-'), _)),
+                Some((Token::Literal(end) | Token::Escaped(end), end_span)),
+            ) => {
+                let span = span.start..end_span.end;
+                patterns.push((
+                    get_range(start, *end, &string[span.clone()], operand, span.start)?,
+                    span,
+                ));
+                i += 3;
+            }
+            _ => {
+                patterns.push((Pattern::Char(start), span.clone()));
+                i += 1;
+            }
+        }
+    }
+
+    Ok(patterns)
+}
+
+/// Join the characters of a set that make up a single extended grapheme
+/// cluster, such as an `e` followed by a combining accent, into one pattern.
+/// Escaped characters are joined too, so `e\u{301}` is a single cluster.
+pub(crate) fn group_graphemes(set: Vec<(Pattern, Range<usize>)>) -> Vec<(Pattern, Range<usize>)> {
+    map_clusters(set, |cluster, span, grouped| {
+        let mut chars = cluster.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => grouped.push((Pattern::Char(c), span)),
+            _ => grouped.push((Pattern::Cluster(cluster.to_string()), span)),
+        }
+    })
+}
+
+/// Normalize the characters of a set, so that they are written the same way
+/// as the normalized input. A cluster that normalizes into several characters,
+/// such as `é` under NFD, stays a single pattern that matches the normalized
+/// cluster, so it still takes up one position and never matches its parts.
+///
+/// A cluster that normalizes into more than one cluster, such as `ﬁ` under
+/// NFKC, could never match the input, so it is an error. Ranges are left as
+/// they are.
+pub(crate) fn normalize_set(
+    set: Vec<(Pattern, Range<usize>)>,
+    form: Normalization,
+    string: &str,
+    operand: Operand,
+) -> Result<Vec<(Pattern, Range<usize>)>, Error> {
+    let mut error = None;
+    let set = map_clusters(set, |cluster, span, normalized| {
+        let cluster = normalize(cluster, form);
+        if error.is_none() && cluster.graphemes(true).nth(1).is_some() {
+            error = Some(Error::SplitByNormalization {
+                operand,
+                character: string[span.clone()].to_string(),
+                normalized: cluster.clone(),
+                offset: span.start,
+            });
+        }
+
+        let mut chars = cluster.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => normalized.push((Pattern::Char(c), span)),
+            _ => normalized.push((Pattern::Cluster(cluster), span)),
+        }
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(set),
+    }
+}
+
+/// Returns true if a cluster is one of the patterns
+pub(crate) fn has_cluster(patterns: &[Pattern], cluster: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| matches!(pattern, Pattern::Cluster(c) if c == cluster))
+}
+
+/// Split the runs of characters in a set into extended grapheme clusters and
+/// replace each one with the patterns `f` adds for it. Other patterns are kept
+/// as they are.
+fn map_clusters(
+    set: Vec<(Pattern, Range<usize>)>,
+    mut f: impl FnMut(&str, Range<usize>, &mut Vec<(Pattern, Range<usize>)>),
+) -> Vec<(Pattern, Range<usize>)> {
+    let mut mapped = Vec::new();
+    let mut run = Vec::new();
+
+    for (pattern, span) in set {
+        match pattern {
+            Pattern::Char(c) => run.push((c, span)),
+            pattern => {
+                map_run(&mut run, &mut mapped, &mut f);
+                mapped.push((pattern, span));
+            }
+        }
+    }
+    map_run(&mut run, &mut mapped, &mut f);
+
+    mapped
+}
+
+/// Split a run of characters from a set into clusters and add the patterns `f`
+/// gives for them to `mapped`
+fn map_run(
+    run: &mut Vec<(char, Range<usize>)>,
+    mapped: &mut Vec<(Pattern, Range<usize>)>,
+    f: &mut impl FnMut(&str, Range<usize>, &mut Vec<(Pattern, Range<usize>)>),
+) {
+    let text: String = run.iter().map(|(c, _)| *c).collect();
+    let mut chars = run.drain(..);
+
+    for cluster in text.graphemes(true) {
+        let mut members = chars.by_ref().take(cluster.chars().count());
+        let (_, first) = members.next().expect("the run holds every character");
+        let end = members.last().map_or(first.end, |(_, last)| last.end);
+        f(cluster, first.start..end, mapped);
+    }
+}
+
+/// The classes that can be written as `[:name:]`, by name
+pub(crate) const CLASSES: [(&str, Pattern); 16] = [
+    ("alnum", Pattern::Alnum),
+    ("alpha", Pattern::Alpha),
+    ("blank", Pattern::Blank),
+    ("cntrl", Pattern::Cntrl),
+    ("digit", Pattern::Digit),
+    ("graph", Pattern::Graph),
+    ("ideogram", Pattern::Ideogram),
+    ("lower", Pattern::Lower),
+    ("phonogram", Pattern::Phonogram),
+    ("print", Pattern::Print),
+    ("punct", Pattern::Punct),
+    ("rune", Pattern::Rune),
+    ("space", Pattern::Space),
+    ("special", Pattern::Special),
+    ("upper", Pattern::Upper),
+    ("xdigit", Pattern::Xdigit),
+];
+
+/// Parse the name of a class written as `[:name:]` into a pattern. An unknown
+/// name comes with the closest class name, if one is close enough to be a typo.
+fn get_class(name: &str, operand: Operand, offset: usize) -> Result<Pattern, Error> {
+    match CLASSES.iter().find(|(class, _)| *class == name) {
+        Some((_, pattern)) => Ok(pattern.clone()),
+        None => Err(Error::UnknownClass {
+            operand,
+            name: name.to_string(),
+            offset,
+            suggestion: suggest_class(name),
+        }),
+    }
+}
+
+/// Find the class name closest to a misspelled one. Names are compared without
+/// case and a name more than two edits away is not suggested.
+fn suggest_class(name: &str) -> Option<String> {
+    let name = name.to_lowercase();
+
+    CLASSES
+        .iter()
+        .map(|(class, _)| (edit_distance(&name, class), *class))
+        .filter(|(distance, _)| *distance <= 2 && *distance < name.chars().count())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, class)| class.to_string())
+}
+
+/// Count the insertions, deletions, substitutions and swaps of two neighbouring
+/// characters needed to turn one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // distances[i][j] holds the distance between the first i characters of a
+    // and the first j characters of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+/// Suggest a repeat with a count that can be read, made from the digits of a
+/// count that cannot. `head` is the repeat up to and including the `*`.
+fn suggest_repeat(head: &str, count: &str) -> Option<String> {
+    let digits: String = count.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    // A count that starts with `0` is octal, so drop the zeros from one that
+    // holds an 8 or a 9
+    let digits = match digits.contains(['8', '9']) {
+        true => digits.trim_start_matches('0'),
+        false => &digits,
+    };
+
+    match digits == count {
+        true => None,
+        false => Some(format!("{}{}]", head, digits)),
+    }
+}
+
+/// Parse a range such as `a-z` into a pattern. A range whose end comes before
+/// its start is an error.
+fn get_range(
+    start: char,
+    end: char,
+    written: &str,
+    operand: Operand,
+    offset: usize,
+) -> Result<Pattern, Error> {
+    if start > end {
+        return Err(Error::InvalidRange {
+            operand,
+            range: written.to_string(),
+            offset,
+        });
+    }
+
+    Ok(Pattern::Range(start, end))
+}
+
+/// The pieces of a set string once escape sequences have been resolved
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A character typed directly into the set string
+    Literal(char),
+    /// A character written as an escape sequence. These never act as the dash
+    /// of a range.
+    Escaped(char),
+    /// The name of a class written as `[:name:]`
+    Class(String),
+    /// The character in an equivalence class written as `[=c=]`
+    Equivalence(char),
+    /// A character and the number of times to repeat it, written as `[c*n]`
+    /// (or `[c*]` without a count)
+    Repeat(char, Option<usize>),
+}
+
+/// Splits a set string into tokens, keeping track of where each one starts so
+/// that errors can point at the part of the set at fault
+#[derive(Clone)]
+struct Lexer<'a> {
+    string: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    operand: Operand,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(string: &'a str, operand: Operand) -> Lexer<'a> {
+        Lexer {
+            string,
+            chars: string.char_indices().peekable(),
+            operand,
+        }
+    }
+
+    /// Returns the offset of the next character, or the length of the string at
+    /// the end
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.string.len(), |(i, _)| *i)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next().map(|(_, c)| c)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    /// Split the set string into characters and classes, resolving any
+    /// backslash escape sequences along the way
+    fn lex(mut self) -> Result<Vec<(Token, Range<usize>)>, Error> {
+        let mut tokens = Vec::new();
+
+        while let Some((start, c)) = self.chars.next() {
+            let token = match c {
+                '\\' => Token::Escaped(self.lex_escape(start)?),
+                // Without a closing `:]`, `=]` or `]` the bracket is just a character
+                '[' if self.peek() == Some(':') => match self.lex_class(start)? {
+                    Some(name) => Token::Class(name),
+                    None => Token::Literal(c),
+                },
+                '[' if self.peek() == Some('=') => match self.lex_equivalence(start)? {
+                    Some(c) => Token::Equivalence(c),
+                    None => Token::Literal(c),
+                },
+                '[' => match self.lex_repeat(start)? {
+                    Some((c, count)) => Token::Repeat(c, count),
+                    None => Token::Literal(c),
+                },
+                c => Token::Literal(c),
+            };
+
+            tokens.push((token, start..self.offset()));
+        }
+
+        Ok(tokens)
+    }
+
+    /// Read the name of a class such as `[:alpha:]`, starting after the opening
+    /// bracket at `start`. A class runs up to the next `:]`. A known class name
+    /// that is not closed properly, as in `[:alpha]`, is taken to be a typo.
+    fn lex_class(&mut self, start: usize) -> Result<Option<String>, Error> {
+        let name_start = self.offset() + 1;
+        let rest = &self.string[name_start..];
+        let end = match rest.find(":]") {
+            Some(len) if len > 0 && !rest[..len].contains(']') => name_start + len,
+            _ => {
+                let name = rest.split(|c: char| !c.is_ascii_alphabetic()).next();
+                let name = name.unwrap_or_default();
+                let after = &rest[name.len()..];
+                if !CLASSES.iter().any(|(class, _)| *class == name) {
+                    return Ok(None);
+                }
+
+                let end = match after {
+                    "" | ":" => self.string.len(),
+                    after if after.starts_with(']') => name_start + name.len() + 1,
+                    _ => return Ok(None),
+                };
+                return Err(Error::Unterminated {
+                    operand: self.operand,
+                    text: self.string[start..end].to_string(),
+                    offset: start,
+                    suggestion: format!("[:{}:]", name),
+                });
+            }
+        };
+
+        // skip the opening colon, the name and the closing `:]`
+        while self.offset() < end + 2 {
+            self.chars.next();
+        }
+
+        Ok(Some(self.string[name_start..end].to_string()))
+    }
+
+    /// Read an equivalence class such as `[=e=]`, starting after the opening
+    /// bracket at `start`. Returns the character in the class, or `None` if the
+    /// string does not hold an equivalence class here. A class that is not
+    /// closed properly, as in `[=e]`, or that holds more than one character is
+    /// taken to be a typo.
+    fn lex_equivalence(&mut self, start: usize) -> Result<Option<char>, Error> {
+        let mut rest = self.clone();
+        rest.next();
+
+        let char_start = rest.offset();
+        let c = match rest.chars.next() {
+            Some((i, '\\')) => rest.lex_escape(i)?,
+            Some((_, c)) => c,
+            None => return Ok(None),
+        };
+        let written = &self.string[char_start..rest.offset()];
+
+        let unterminated = |end: usize| Error::Unterminated {
+            operand: self.operand,
+            text: self.string[start..end].to_string(),
+            offset: start,
+            suggestion: format!("[={}=]", written),
+        };
+
+        match (rest.next(), rest.peek()) {
+            (Some('='), Some(']')) => {
+                rest.next();
+                *self = rest;
+                Ok(Some(c))
+            }
+            (Some('='), None) => Err(unterminated(self.string.len())),
+            (Some(']'), _) if c != '=' => Err(unterminated(rest.offset())),
+            _ => {
+                // Several plain characters closed by `=]` were most likely meant
+                // as one equivalence class each
+                let body = &self.string[char_start..];
+                match body.find("=]").map(|len| &body[..len]) {
+                    Some(chars)
+                        if chars.chars().count() > 1 && !chars.contains(['[', ']', '\\']) =>
+                    {
+                        Err(Error::InvalidEquivalence {
+                            operand: self.operand,
+                            equivalence: self.string[start..char_start + chars.len() + 2]
+                                .to_string(),
+                            offset: start,
+                            suggestion: chars.chars().map(|c| format!("[={}=]", c)).collect(),
+                        })
+                    }
+                    _ => Ok(None),
+                }
+            }
+        }
+    }
+
+    /// Read a repeat such as `[c*3]` or `[c*]`, starting after the opening
+    /// bracket at `start`. Returns the character and the count, or `None` if the
+    /// string does not hold a repeat here. A count that starts with `0` is octal
+    /// and a count of zero is the same as no count.
+    fn lex_repeat(&mut self, start: usize) -> Result<Option<(char, Option<usize>)>, Error> {
+        let mut rest = self.clone();
+
+        let c = match rest.chars.next() {
+            Some((i, '\\')) => rest.lex_escape(i)?,
+            Some((_, c)) => c,
+            None => return Ok(None),
+        };
+
+        if rest.next() != Some('*') {
+            return Ok(None);
+        }
+
+        let count_start = rest.offset();
+        let count_end = match rest.string[count_start..].find(']') {
+            Some(len) => count_start + len,
+            // A repeat that runs to the end of the string without its closing
+            // bracket is taken to be a typo
+            None if rest.string[count_start..]
+                .bytes()
+                .all(|b| b.is_ascii_digit()) =>
+            {
+                let repeat = &self.string[start..];
+                return Err(Error::Unterminated {
+                    operand: self.operand,
+                    text: repeat.to_string(),
+                    offset: start,
+                    suggestion: format!("{}]", repeat),
+                });
+            }
+            None => return Ok(None),
+        };
+
+        // skip the count and the closing bracket
+        while rest.offset() <= count_end {
+            rest.chars.next();
+        }
+
+        let digits = &rest.string[count_start..count_end];
+        let radix = if digits.starts_with('0') { 8 } else { 10 };
+        let count = match digits {
+            "" => None,
+            digits => match usize::from_str_radix(digits, radix) {
+                Ok(0) => None,
+                Ok(count) => Some(count),
+                Err(_) => {
+                    return Err(Error::InvalidRepeat {
+                        operand: self.operand,
+                        repeat: self.string[start..rest.offset()].to_string(),
+                        offset: start,
+                        suggestion: suggest_repeat(&self.string[start..count_start], digits),
+                    })
+                }
+            },
+        };
+
+        *self = rest;
+        Ok(Some((c, count)))
+    }
+
+    /// Resolve the escape sequence following the backslash at `start`. Supports
+    /// the POSIX escapes (`\a \b \f \n \r \t \v \\` and one to three octal
+    /// digits) as well as `\xHH` and `\u{...}`.
+    fn lex_escape(&mut self, start: usize) -> Result<char, Error> {
+        let c = match self.next() {
+            Some(c) => c,
+            None => return Err(self.bad_escape(start)),
+        };
+
+        let escaped = match c {
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '0'..='7' => {
+                // Take up to three octal digits as long as the value fits in a byte
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.peek().and_then(|d| d.to_digit(8)) {
+                        Some(digit) if value * 8 + digit <= 0o377 => {
+                            value = value * 8 + digit;
+                            self.next();
+                        }
+                        _ => break,
+                    }
+                }
+                char::from_u32(value).unwrap()
+            }
+            'x' => {
+                let mut value = None;
+                for _ in 0..2 {
+                    match self.peek().and_then(|d| d.to_digit(16)) {
+                        Some(digit) => {
+                            value = Some(value.unwrap_or(0) * 16 + digit);
+                            self.next();
+                        }
+                        None => break,
+                    }
+                }
+                match value {
+                    Some(value) => char::from_u32(value).unwrap(),
+                    None => return Err(self.bad_escape(start)),
+                }
+            }
+            'u' => {
+                if self.next() != Some('{') {
+                    return Err(self.bad_escape(start));
+                }
+                let mut digits = String::new();
+                loop {
+                    match self.next() {
+                        Some('}') => break,
+                        Some(d) => digits.push(d),
+                        // The sequence was never closed
+                        None => return Err(self.bad_escape(start)),
+                    }
+                }
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| (1..=6).contains(&digits.len()))
+                    .and_then(char::from_u32)
+                {
+                    Some(c) => c,
+                    None => return Err(self.bad_escape(start)),
+                }
+            }
+            // Punctuation such as `\-` or `\[` stands for itself
+            c if c.is_ascii_punctuation() => c,
+            _ => return Err(self.bad_escape(start)),
+        };
+
+        Ok(escaped)
+    }
+
+    /// The error for an escape sequence that starts at `start` and runs up to
+    /// the next character
+    fn bad_escape(&mut self, start: usize) -> Error {
+        Error::BadEscape {
+            operand: self.operand,
+            sequence: self.string[start..self.offset()].to_string(),
+            offset: start,
+        }
+    }
+}
+
+/// Returns true if a character should be processed
+pub(crate) fn check_char(pattern: &Pattern, character: &char) -> bool {
+    match pattern {
+        Pattern::Alnum => character.is_alphanumeric(),
+        Pattern::Alpha => character.is_alphabetic(),
+        Pattern::Blank => is_blank(*character),
+        Pattern::Cntrl => character.is_control(),
+        Pattern::Digit => character.is_numeric(),
+        Pattern::Graph => is_graph(*character),
+        Pattern::Ideogram => is_ideogram(*character),
+        Pattern::Lower => character.is_lowercase(),
+        Pattern::Phonogram => is_phonogram(*character),
+        Pattern::Print => is_print(*character),
+        Pattern::Punct => is_punct(*character),
+        Pattern::Rune => is_rune(*character),
+        Pattern::Space => character.is_whitespace(),
+        Pattern::Special => is_special(*character),
+        Pattern::Upper => character.is_uppercase(),
+        Pattern::Xdigit => is_xdigit(*character),
+        Pattern::Char(c) => c == character,
+        // A cluster is always more than one character
+        Pattern::Cluster(_) => false,
+        Pattern::Range(start, end) => (*start..=*end).contains(character),
+        Pattern::Equivalence(e) => is_equivalent(*character, *e),
+        Pattern::Repeat(c, _) => c == character,
+    }
+}
+
+/// Returns true for whitespace that separates words on a line, such as spaces
+/// and tabs. Unlike `[:space:]` this does not include line breaks.
+fn is_blank(c: char) -> bool {
+    c.is_whitespace()
+        && !matches!(
+            c,
+            '\n' | '\x0B' | '\x0C' | '\r' | '\u{85}' | '\u{2028}' | '\u{2029}'
+        )
+}
+
+/// Returns true for printable characters other than whitespace. ASCII characters
+/// follow the POSIX definition and every other character is graphic if it is
+/// assigned and is not a control, format or separator character. Letters and
+/// numbers are also looked up in the standard library and marks in
+/// `unicode_normalization`, which can follow a newer version of Unicode than
+/// `GRAPH_RANGES`, so whatever `[:alpha:]` matches is always graphic.
+fn is_graph(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_graphic()
+    } else {
+        in_ranges(GRAPH_RANGES, c) || c.is_alphanumeric() || is_combining_mark(c)
+    }
+}
+
+/// Returns true for printable characters, which are the graphic characters plus
+/// spaces. ASCII characters follow the POSIX definition and every other
+/// character is printable if it is graphic or a space such as `\u{A0}`.
+fn is_print(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_graphic() || c == ' '
+    } else {
+        is_graph(c) || is_blank(c)
+    }
+}
+
+/// Returns true for punctuation and symbols. In ASCII this is the POSIX
+/// punctuation class, and the rest of Unicode adds the characters in the
+/// punctuation and symbol categories, but not combining marks or format
+/// characters.
+pub(crate) fn is_punct(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_punctuation()
+    } else {
+        in_ranges(PUNCT_RANGES, c)
+    }
+}
+
+/// Returns true for hexadecimal digits. Besides the POSIX `0-9`, `a-f` and `A-F`
+/// this includes their fullwidth forms.
+fn is_xdigit(c: char) -> bool {
+    c.is_ascii_hexdigit()
+        || ('\u{FF10}'..='\u{FF19}').contains(&c)
+        || ('\u{FF21}'..='\u{FF26}').contains(&c)
+        || ('\u{FF41}'..='\u{FF46}').contains(&c)
+}
+
+/// The Han ideographs matched by `[:ideogram:]`: the CJK unified and
+/// compatibility ideograph blocks plus the ideographic numbers.
+const IDEOGRAM_RANGES: &[(char, char)] = &[
+    ('\u{3006}', '\u{3007}'),
+    ('\u{3021}', '\u{3029}'),
+    ('\u{3038}', '\u{303A}'),
+    ('\u{3400}', '\u{4DBF}'),
+    ('\u{4E00}', '\u{9FFF}'),
+    ('\u{F900}', '\u{FAFF}'),
+    ('\u{20000}', '\u{2A6DF}'),
+    ('\u{2A700}', '\u{2EBEF}'),
+    ('\u{2F800}', '\u{2FA1F}'),
+    ('\u{30000}', '\u{323AF}'),
+];
+
+/// The syllabaries matched by `[:phonogram:]`: Kana, Bopomofo and Hangul
+const PHONOGRAM_RANGES: &[(char, char)] = &[
+    ('\u{1100}', '\u{11FF}'),
+    ('\u{3041}', '\u{3096}'),
+    ('\u{309D}', '\u{309F}'),
+    ('\u{30A1}', '\u{30FA}'),
+    ('\u{30FC}', '\u{30FF}'),
+    ('\u{3105}', '\u{312F}'),
+    ('\u{3131}', '\u{318E}'),
+    ('\u{31A0}', '\u{31BF}'),
+    ('\u{31F0}', '\u{31FF}'),
+    ('\u{A960}', '\u{A97F}'),
+    ('\u{AC00}', '\u{D7A3}'),
+    ('\u{D7B0}', '\u{D7FF}'),
+    ('\u{FF66}', '\u{FF9D}'),
+    ('\u{1AFF0}', '\u{1B16F}'),
+];
+
+/// Returns true for Han ideographs
+fn is_ideogram(c: char) -> bool {
+    IDEOGRAM_RANGES
+        .iter()
+        .any(|(start, end)| (*start..=*end).contains(&c))
+}
+
+/// Returns true for characters from phonetic scripts such as Hiragana,
+/// Katakana, Bopomofo and Hangul
+fn is_phonogram(c: char) -> bool {
+    PHONOGRAM_RANGES
+        .iter()
+        .any(|(start, end)| (*start..=*end).contains(&c))
+}
+
+/// Returns true for every valid character, as every `char` is a Unicode
+/// scalar value
+fn is_rune(_c: char) -> bool {
+    true
+}
+
+/// Returns true for punctuation and symbols outside of ASCII, such as `¿`, `€`
+/// or `→`. Combining accents are not symbols, so they are left out.
+fn is_special(c: char) -> bool {
+    !c.is_ascii() && is_punct(c)
+}
+
+/// Replace every range with the individual characters it covers
+pub(crate) fn expand_ranges(patterns: Vec<Pattern>) -> Vec<Pattern> {
+    patterns
+        .into_iter()
+        .flat_map(|pattern| match pattern {
+            Pattern::Range(start, end) => (start..=end).map(Pattern::Char).collect(),
+            pattern => vec![pattern],
+        })
+        .collect()
+}
+
+/// Restrict a set to byte values for byte mode, where each byte is stood in for
+/// by the character with the same value. As in the C locale, classes only match
+/// ASCII characters (apart from `[:rune:]`, which matches every byte) and an
+/// equivalence class only matches its own character.
+pub(crate) fn byte_patterns(patterns: Vec<Pattern>) -> Vec<Pattern> {
+    let mut bytes = Vec::new();
+    for pattern in patterns {
+        match pattern {
+            Pattern::Char(c) | Pattern::Equivalence(c) => bytes.push(Pattern::Char(c)),
+            Pattern::Range(..) | Pattern::Repeat(..) => bytes.push(pattern),
+            Pattern::Rune => bytes.push(Pattern::Range('\0', '\u{ff}')),
+            class => {
+                // Turn the class into runs of ASCII characters so that classes
+                // in string1 and string2 pair up by position
+                let mut members = ('\0'..='\x7F').filter(|c| check_char(&class, c)).peekable();
+                while let Some(start) = members.next() {
+                    let mut end = start;
+                    while members.next_if(|c| *c as u32 == end as u32 + 1).is_some() {
+                        end = char::from_u32(end as u32 + 1).unwrap();
+                    }
+                    bytes.push(match start == end {
+                        true => Pattern::Char(start),
+                        false => Pattern::Range(start, end),
+                    });
+                }
+            }
+        }
+    }
+
+    bytes
+}
+
+/// Split the characters written as themselves in a set into their UTF-8 bytes,
+/// as `tr` does in the C locale, so that `é` matches the bytes `\303\251`
+/// rather than the byte `\351`. Escapes already stand for a single byte.
+pub(crate) fn literal_bytes(
+    set: Vec<(Pattern, Range<usize>)>,
+    string: &str,
+) -> Vec<(Pattern, Range<usize>)> {
+    let mut bytes = Vec::new();
+    for (pattern, span) in set {
+        match pattern {
+            Pattern::Char(c) if !c.is_ascii() && string[span.clone()] == *c.to_string() => {
+                for b in c.to_string().bytes() {
+                    bytes.push((Pattern::Char(char::from(b)), span.clone()));
+                }
+            }
+            _ => bytes.push((pattern, span)),
+        }
+    }
+
+    bytes
+}
+
+/// Check that every character in a set is a byte value, pointing at the first
+/// one that is not. A character outside ASCII that is written as itself in a
+/// range, an equivalence class or a repeat is more than one byte, so it has to
+/// be written as an escape instead.
+pub(crate) fn check_bytes(
+    set: &[(Pattern, Range<usize>)],
+    string: &str,
+    operand: Operand,
+) -> Result<(), Error> {
+    for (pattern, span) in set {
+        let (first, last) = match pattern {
+            Pattern::Char(c) | Pattern::Equivalence(c) | Pattern::Repeat(c, _) => (*c, *c),
+            Pattern::Range(start, end) => (*start, *end),
+            _ => continue,
+        };
+
+        let written = &string[span.clone()];
+        let literal = |c: char| !c.is_ascii() && written.contains(c);
+        if last > '\u{ff}' || literal(first) || literal(last) {
+            return Err(Error::NotAByte {
+                operand,
+                character: string[span.clone()].to_string(),
+                offset: span.start,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the base letter of a character. Characters whose canonical
+/// decomposition is a letter followed by combining marks (such as `é`) have that
+/// letter as their base, every other character is its own base.
+pub(crate) fn base_letter(c: char) -> char {
+    let mut decomposition = Vec::new();
+    decompose_canonical(c, |d| decomposition.push(d));
+
+    match decomposition.split_first() {
+        Some((base, marks)) if marks.iter().all(|m| is_combining_mark(*m)) => *base,
+        _ => c,
+    }
+}
+
+/// Bring text into one of the Unicode normalization forms
+pub(crate) fn normalize(text: &str, form: Normalization) -> String {
+    match form {
+        Normalization::Nfc => text.nfc().collect(),
+        Normalization::Nfd => text.nfd().collect(),
+        Normalization::Nfkc => text.nfkc().collect(),
+        Normalization::Nfkd => text.nfkd().collect(),
+    }
+}
+
+/// Returns true if two characters share a base letter
+fn is_equivalent(c: char, other: char) -> bool {
+    base_letter(c) == base_letter(other)
+}
+
+/// Returns true if any of the patterns match a character
+pub(crate) fn check_chars(patterns: &[Pattern], character: &char) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| check_char(pattern, character))
+}
+
+/// Returns true if any of the patterns match an extended grapheme cluster of
+/// more than one character. A cluster only matches the same cluster, while
+/// ranges, classes and equivalence classes match a cluster by its first
+/// character.
+pub(crate) fn check_cluster(patterns: &[Pattern], cluster: &str) -> bool {
+    let Some(first) = cluster.chars().next() else {
+        return false;
+    };
+
+    patterns.iter().any(|pattern| match pattern {
+        Pattern::Cluster(other) => other == cluster,
+        Pattern::Char(_) | Pattern::Repeat(..) => false,
+        pattern => check_char(pattern, &first),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse string1 into its patterns
+    fn get_patterns(string: &str) -> Result<Vec<Pattern>, Error> {
+        let patterns = parse(string, Operand::String1)?;
+
+        Ok(patterns.into_iter().map(|(pattern, _)| pattern).collect())
+    }
+
+    // ************************************************************************
+    // parse tests
+    // ************************************************************************
+
+    #[test]
+    fn octal_escape_stops_at_one_byte() {
+        let patterns = get_patterns("\\4001").unwrap();
+
+        assert!(matches!(
+            patterns.as_slice(),
+            [Pattern::Char(' '), Pattern::Char('0'), Pattern::Char('1')]
+        ));
+    }
+
+    #[test]
+    fn unknown_escape_returns_error() {
+        let result = get_patterns("a\\qb");
+
+        assert_eq!(
+            format!("{}", result.unwrap_err()),
+            "Invalid escape sequence `\\q`."
+        );
+    }
+
+    #[test]
+    fn trailing_backslash_returns_error() {
+        let result = get_patterns("ab\\");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn hex_escape_without_digits_returns_error() {
+        let result = get_patterns("\\xg");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_unicode_escape_returns_error() {
+        assert!(get_patterns("\\u{110000}").is_err());
+        assert!(get_patterns("\\u{d800}").is_err());
+        assert!(get_patterns("\\u{}").is_err());
+        assert!(get_patterns("\\u41").is_err());
+    }
+
+    #[test]
+    fn unclosed_unicode_escape_returns_error() {
+        let result = parse("\\u{41", Operand::String1);
+
+        assert!(matches!(
+            result,
+            Err(Error::BadEscape {
+                ref sequence,
+                offset: 0,
+                ..
+            }) if sequence == "\\u{41"
+        ));
+    }
+
+    #[test]
+    fn unknown_class_returns_its_offset() {
+        let result = parse("a[:alhpa:]", Operand::String2);
+
+        assert!(matches!(
+            result,
+            Err(Error::UnknownClass {
+                operand: Operand::String2,
+                ref name,
+                offset: 1,
+                ..
+            }) if name == "alhpa"
+        ));
+    }
+
+    #[test]
+    fn bad_escape_returns_its_offset() {
+        let result = parse("ab\\q", Operand::String1);
+
+        assert!(matches!(
+            result,
+            Err(Error::BadEscape {
+                ref sequence,
+                offset: 2,
+                ..
+            }) if sequence == "\\q"
+        ));
+    }
+
+    #[test]
+    fn invalid_range_returns_the_range_as_written() {
+        let result = parse("a-c\\x7a-a", Operand::String1);
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidRange {
+                ref range,
+                offset: 3,
+                ..
+            }) if range == "\\x7a-a"
+        ));
+    }
+
+    #[test]
+    fn misspelled_class_suggests_the_closest_class() {
+        let suggestion = |set: &str| match parse(set, Operand::String1) {
+            Err(Error::UnknownClass { suggestion, .. }) => suggestion,
+            result => panic!("expected an unknown class, got {:?}", result),
+        };
+
+        assert_eq!(suggestion("[:uper:]"), Some("upper".to_string()));
+        assert_eq!(suggestion("[:digits:]"), Some("digit".to_string()));
+        assert_eq!(suggestion("[:alhpa:]"), Some("alpha".to_string()));
+        assert_eq!(suggestion("[:LOWER:]"), Some("lower".to_string()));
+        assert_eq!(suggestion("[:emoji:]"), None);
+    }
+
+    #[test]
+    fn edit_distance_counts_swaps_as_one_edit() {
+        assert_eq!(edit_distance("upper", "upper"), 0);
+        assert_eq!(edit_distance("uper", "upper"), 1);
+        assert_eq!(edit_distance("alhpa", "alpha"), 1);
+        assert_eq!(edit_distance("spcae", "space"), 1);
+        assert_eq!(edit_distance("", "rune"), 4);
+    }
+
+    #[test]
+    fn unterminated_class_returns_error() {
+        let result = parse("a[:upper]", Operand::String1);
+
+        assert!(matches!(
+            result,
+            Err(Error::Unterminated {
+                ref text,
+                ref suggestion,
+                offset: 1,
+                ..
+            }) if text == "[:upper]" && suggestion == "[:upper:]"
+        ));
+        assert!(matches!(
+            parse("[:digit:", Operand::String1),
+            Err(Error::Unterminated { .. })
+        ));
+    }
+
+    #[test]
+    fn brackets_that_are_not_a_class_stay_characters() {
+        let patterns = get_patterns("[:x]").unwrap();
+
+        assert_eq!(
+            patterns,
+            vec![
+                Pattern::Char('['),
+                Pattern::Char(':'),
+                Pattern::Char('x'),
+                Pattern::Char(']')
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_equivalence_class_returns_error() {
+        let suggestion = |set: &str| match parse(set, Operand::String1) {
+            Err(Error::Unterminated { suggestion, .. }) => suggestion,
+            result => panic!("expected an unterminated class, got {:?}", result),
+        };
+
+        assert_eq!(suggestion("[=e]"), "[=e=]");
+        assert_eq!(suggestion("ab[=e="), "[=e=]");
+        assert_eq!(suggestion("[=\\x65]"), "[=\\x65=]");
+    }
+
+    #[test]
+    fn equivalence_class_with_several_characters_returns_error() {
+        let result = parse("[=ae=]x", Operand::String1);
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidEquivalence {
+                ref equivalence,
+                ref suggestion,
+                ..
+            }) if equivalence == "[=ae=]" && suggestion == "[=a=][=e=]"
+        ));
+    }
+
+    #[test]
+    fn unterminated_repeat_returns_error() {
+        let result = parse("[a*3", Operand::String2);
+
+        assert!(matches!(
+            result,
+            Err(Error::Unterminated { ref suggestion, .. }) if suggestion == "[a*3]"
+        ));
+    }
+
+    #[test]
+    fn invalid_repeat_count_suggests_a_count() {
+        let suggestion = |set: &str| match parse(set, Operand::String2) {
+            Err(Error::InvalidRepeat { suggestion, .. }) => suggestion,
+            result => panic!("expected an invalid repeat, got {:?}", result),
+        };
+
+        assert_eq!(suggestion("[a*x]"), Some("[a*]".to_string()));
+        assert_eq!(suggestion("[a*08]"), Some("[a*8]".to_string()));
+    }
+
+    #[test]
+    fn invalid_repeat_count_returns_error() {
+        let result = get_patterns("[a*3x]");
+
+        assert_eq!(
+            format!("{}", result.unwrap_err()),
+            "Invalid repeat count `3x` in `[a*3x]`. Did you mean `[a*3]`?"
+        );
+        assert!(get_patterns("[a*08]").is_err());
+    }
+
+    #[test]
+    fn offsets_count_bytes_after_multibyte_characters() {
+        let result = parse("é[:nope:]", Operand::String1);
+
+        assert!(matches!(result, Err(Error::UnknownClass { offset: 2, .. })));
+    }
+
+    // ************************************************************************
+    // class tests
+    // ************************************************************************
+
+    #[test]
+    fn graph_class_matches_letters_newer_than_its_table() {
+        for c in ['\u{31350}', '\u{2EBF0}', '\u{1E030}', '\u{11F00}'] {
+            assert!(check_char(&Pattern::Alpha, &c) || is_combining_mark(c));
+            assert!(is_graph(c));
+            assert!(is_print(c));
+        }
+    }
+
+    #[test]
+    fn base_letter_removes_combining_marks() {
+        assert_eq!(base_letter('é'), 'e');
+        assert_eq!(base_letter('Ü'), 'U');
+        assert_eq!(base_letter('ñ'), 'n');
+        assert_eq!(base_letter('e'), 'e');
+        assert_eq!(base_letter('ß'), 'ß');
+        assert_eq!(base_letter('한'), '한');
+    }
+
+    // ************************************************************************
+    // grapheme tests
+    // ************************************************************************
+
+    #[test]
+    fn characters_of_a_set_are_grouped_into_clusters() {
+        let set = group_graphemes(parse("ae\u{301}b[:digit:]", Operand::String1).unwrap());
+
+        assert_eq!(
+            set,
+            vec![
+                (Pattern::Char('a'), 0..1),
+                (Pattern::Cluster("e\u{301}".to_string()), 1..4),
+                (Pattern::Char('b'), 4..5),
+                (Pattern::Digit, 5..14),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_characters_are_grouped_into_clusters() {
+        let set = group_graphemes(parse("e\\u{301}", Operand::String1).unwrap());
+
+        assert_eq!(set, vec![(Pattern::Cluster("e\u{301}".to_string()), 0..8)]);
+    }
+
+    // ************************************************************************
+    // normalization tests
+    // ************************************************************************
+
+    #[test]
+    fn normalized_set_characters_keep_the_span_of_their_cluster() {
+        let set = parse("e\\u{301}x", Operand::String1).unwrap();
+
+        assert_eq!(
+            normalize_set(set, Normalization::Nfc, "e\\u{301}x", Operand::String1).unwrap(),
+            vec![(Pattern::Char('é'), 0..8), (Pattern::Char('x'), 8..9)]
+        );
+    }
+
+    #[test]
+    fn decomposed_set_character_stays_a_single_pattern() {
+        let set = parse("éa", Operand::String1).unwrap();
+
+        assert_eq!(
+            normalize_set(set, Normalization::Nfd, "éa", Operand::String1).unwrap(),
+            vec![
+                (Pattern::Cluster("e\u{301}".to_string()), 0..2),
+                (Pattern::Char('a'), 2..3)
+            ]
+        );
+    }
+
+    #[test]
+    fn set_character_normalized_into_several_characters_returns_error() {
+        let set = parse("aﬁ", Operand::String2).unwrap();
+
+        let result = normalize_set(set, Normalization::Nfkc, "aﬁ", Operand::String2);
+
+        assert!(matches!(
+            result,
+            Err(Error::SplitByNormalization {
+                operand: Operand::String2,
+                offset: 1,
+                ..
+            })
+        ));
+    }
+
+    // ************************************************************************
+    // byte mode tests
+    // ************************************************************************
+
+    #[test]
+    fn range_written_with_characters_above_ascii_returns_error_in_byte_mode() {
+        let set = parse("à-é", Operand::String1).unwrap();
+
+        let result = check_bytes(&literal_bytes(set, "à-é"), "à-é", Operand::String1);
+
+        assert!(matches!(result, Err(Error::NotAByte { offset: 0, .. })));
+    }
+
+    #[test]
+    fn byte_mode_keeps_classes_in_string2_positional() {
+        let patterns = byte_patterns(vec![Pattern::Digit, Pattern::Alpha]);
+
+        assert_eq!(
+            patterns,
+            vec![
+                Pattern::Range('0', '9'),
+                Pattern::Range('A', 'Z'),
+                Pattern::Range('a', 'z')
+            ]
+        );
+    }
+}
@@ -0,0 +1,796 @@
+use crate::args::{CaseMapping, CaseRules};
+use crate::error::Error;
+use crate::set::{
+    base_letter, byte_patterns, check_char, check_chars, check_cluster, expand_ranges, has_cluster,
+    is_punct, Pattern,
+};
+
+use std::collections::HashMap;
+use unicode_normalization::char::{canonical_combining_class, is_combining_mark};
+use unicode_normalization::UnicodeNormalization;
+
+/// string1 and string2 compiled into a single lookup structure.
+///
+/// string1 is read as a list of characters (with ranges expanded) and sets (a
+/// class, an equivalence class or the complement of string1). A class or the
+/// complement stands for its members in code point order, so they pair up one
+/// by one with the characters in string2, as GNU tr does. The complement can
+/// also be put in collation sequence, which is how POSIX orders it for `-c`. An equivalence class
+/// has no meaningful order and takes up a single position. string2 is padded
+/// using its last pattern, and once a set meets the last pattern or a class in
+/// string2 the rest of its members are translated into that pattern.
+///
+/// Each input character is looked up once, so the output of one mapping is
+/// never translated again (`ab` to `bc` turns `a` into `b`, not `c`). When more
+/// than one part of string1 matches a character, the one that comes last in
+/// string1 wins.
+#[derive(Debug, Clone)]
+pub(crate) struct TranslationTable {
+    /// The translation of every ASCII character, worked out up front
+    ascii: Vec<Mapped>,
+    /// The characters in string1, their position and what they translate into
+    chars: HashMap<char, (usize, Mapped)>,
+    /// The clusters in string1 in grapheme mode, their position and what they
+    /// translate into
+    clusters: HashMap<String, (usize, Mapped)>,
+    /// The sets in string1 and what the rest of their members translate into
+    classes: Vec<ClassMapping>,
+    /// How the case of a character is changed
+    case: Case,
+}
+
+/// How the case of a character is changed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Case {
+    /// Full or simple case mappings
+    pub(crate) mapping: CaseMapping,
+    /// The language the case rules come from
+    pub(crate) rules: CaseRules,
+}
+
+/// What a character is translated into
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Mapped {
+    /// A single character, which is what most characters translate into
+    Char(char),
+    /// Several characters, from a full case mapping such as `ß` into `SS`
+    Chars(String),
+    /// A lowercase sigma, which is `ς` at the end of a word and `σ` elsewhere
+    Sigma,
+    /// A letter whose case mapping depends on the combining marks after it, such
+    /// as a Lithuanian `I` that keeps its dot under an accent
+    Marked { letter: char, upper: bool },
+}
+
+/// A set from string1 and the pattern from string2 the rest of its members
+/// translate into
+#[derive(Debug, Clone)]
+struct ClassMapping {
+    /// The position of the set in string1
+    index: usize,
+    /// The patterns the mapping applies to. Members that have already been
+    /// paired with a character in string2 are in `chars` under the same index.
+    patterns: Vec<Pattern>,
+    /// True if the mapping applies to every character `patterns` does not match
+    complement: bool,
+    /// The pattern in string2 to translate into
+    target: Pattern,
+}
+
+/// A part of string1 that pairs up with string2
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    /// A single character
+    Char(char),
+    /// An extended grapheme cluster, in grapheme mode
+    Cluster(String),
+    /// Every character matched by a class or an equivalence class, or every
+    /// character string1 does not match when `class` is `None`
+    Set {
+        class: Option<Pattern>,
+        patterns: Vec<Pattern>,
+        complement: bool,
+    },
+}
+
+/// string2 as runs of the same pattern, read one position at a time
+#[derive(Debug)]
+struct Targets {
+    /// Each pattern and the number of positions it takes up
+    runs: Vec<(Pattern, usize)>,
+    /// The run being read
+    run: usize,
+    /// The number of positions of that run that have been used
+    used: usize,
+}
+
+impl TranslationTable {
+    /// Compile string1 and string2. When `complement` is true string1 is
+    /// replaced by every character it does not match, in collation sequence
+    /// when `collate` is true. In byte mode sets only hold byte values and
+    /// classes only match ASCII characters.
+    pub(crate) fn new(
+        patterns1: Vec<Pattern>,
+        patterns2: Vec<Pattern>,
+        complement: bool,
+        collate: bool,
+        case: Case,
+        bytes: bool,
+    ) -> Result<Self, Error> {
+        let mut table = TranslationTable {
+            ascii: Vec::with_capacity(128),
+            chars: HashMap::new(),
+            clusters: HashMap::new(),
+            classes: Vec::new(),
+            case,
+        };
+
+        let last = match bytes {
+            true => '\u{ff}',
+            false => char::MAX,
+        };
+        let set_patterns = |patterns: Vec<Pattern>| match bytes {
+            true => byte_patterns(patterns),
+            false => patterns,
+        };
+
+        let sources = match complement {
+            true => vec![Source::Set {
+                class: None,
+                patterns: set_patterns(patterns1),
+                complement: true,
+            }],
+            false => expand_ranges(patterns1)
+                .into_iter()
+                .map(|pattern| match pattern {
+                    Pattern::Char(c) => Source::Char(c),
+                    Pattern::Cluster(cluster) => Source::Cluster(cluster),
+                    Pattern::Equivalence(c) if bytes => Source::Char(c),
+                    class => Source::Set {
+                        class: Some(class.clone()),
+                        patterns: set_patterns(vec![class]),
+                        complement: false,
+                    },
+                })
+                .collect(),
+        };
+
+        let mut targets = Targets::new(patterns2, &sources, last, bytes);
+
+        for (index, source) in sources.into_iter().enumerate() {
+            match source {
+                Source::Char(c) => {
+                    let target = targets.next();
+                    check_target(&Pattern::Char(c), &target)?;
+                    table.chars.insert(c, (index, map_char(c, &target, case)));
+                }
+                Source::Cluster(cluster) => {
+                    let target = targets.next();
+                    check_target(&Pattern::Cluster(cluster.clone()), &target)?;
+                    let mapped = map_cluster(&cluster, &target, case);
+                    table.clusters.insert(cluster, (index, mapped));
+                }
+                Source::Set {
+                    class,
+                    patterns,
+                    complement,
+                } => {
+                    let members = ('\0'..=last).filter(|c| check_chars(&patterns, c) != complement);
+                    let members: Box<dyn Iterator<Item = char>> =
+                        match collate && class.is_none() && !bytes {
+                            true => Box::new(collated(members, targets.singles()).into_iter()),
+                            false => Box::new(members),
+                        };
+                    let mut members = members.peekable();
+
+                    // Pair members with characters in string2 one by one until
+                    // string2 runs out or reaches a class
+                    let whole = matches!(class, Some(Pattern::Equivalence(_)));
+                    while let Some(&c) = members.peek() {
+                        let (target, is_last) = targets.peek();
+                        let is_class = !matches!(target, Pattern::Char(_) | Pattern::Cluster(_));
+                        if whole || is_last || is_class {
+                            let from = class.clone().unwrap_or(Pattern::Char(c));
+                            let target = targets.next();
+                            check_target(&from, &target)?;
+                            table.classes.push(ClassMapping {
+                                index,
+                                patterns: patterns.clone(),
+                                complement,
+                                target,
+                            });
+                            break;
+                        }
+
+                        let target = targets.next();
+                        table.chars.insert(c, (index, map_char(c, &target, case)));
+                        members.next();
+                    }
+                }
+            }
+        }
+
+        for c in '\0'..='\x7F' {
+            let mapped = table.lookup(c);
+            table.ascii.push(mapped);
+        }
+
+        Ok(table)
+    }
+
+    /// Translate a single character
+    pub(crate) fn get(&self, c: char) -> Mapped {
+        match self.ascii.get(c as usize) {
+            Some(mapped) => mapped.clone(),
+            None => self.lookup(c),
+        }
+    }
+
+    /// Find the mapping that comes last in string1 for a character and apply it.
+    /// Characters that no mapping matches are left as they are.
+    fn lookup(&self, c: char) -> Mapped {
+        let char_mapping = self.chars.get(&c);
+
+        for mapping in self.classes.iter().rev() {
+            // A member of the set that was paired one by one keeps its pairing
+            if char_mapping.is_some_and(|(index, _)| *index >= mapping.index) {
+                break;
+            }
+
+            if mapping.matches(c) {
+                return map_char(c, &mapping.target, self.case);
+            }
+        }
+
+        char_mapping.map_or(Mapped::Char(c), |(_, mapped)| mapped.clone())
+    }
+
+    /// Translate an extended grapheme cluster of more than one character, or
+    /// return `None` when no mapping matches it. A set translates a cluster the
+    /// way it translates the cluster's first character.
+    pub(crate) fn get_cluster(&self, cluster: &str) -> Option<Mapped> {
+        let first = cluster.chars().next()?;
+        let cluster_mapping = self.clusters.get(cluster);
+
+        for mapping in self.classes.iter().rev() {
+            if cluster_mapping.is_some_and(|(index, _)| *index > mapping.index) {
+                break;
+            }
+
+            if check_cluster(&mapping.patterns, cluster) != mapping.complement {
+                // Members paired with characters in string2 one by one keep
+                // their pairing
+                return Some(match self.chars.get(&first) {
+                    Some((index, Mapped::Marked { letter, upper })) if *index == mapping.index => {
+                        let marks = &cluster[first.len_utf8()..];
+                        let (letter, marks) = self.map_marked(*letter, *upper, marks);
+                        Mapped::Chars(letter + &marks)
+                    }
+                    Some((index, mapped)) if *index == mapping.index => mapped.clone(),
+                    _ => map_cluster(cluster, &mapping.target, self.case),
+                });
+            }
+        }
+
+        cluster_mapping.map(|(_, mapped)| mapped.clone())
+    }
+
+    /// Returns true if string1 holds an extended grapheme cluster as a whole
+    pub(crate) fn has_cluster(&self, cluster: &str) -> bool {
+        self.clusters.contains_key(cluster)
+            || self
+                .classes
+                .iter()
+                .any(|mapping| has_cluster(&mapping.patterns, cluster))
+    }
+
+    /// Change the case of a letter that was translated into `Mapped::Marked`,
+    /// now that the combining marks after it are known. Returns the new letter
+    /// and the marks to keep.
+    pub(crate) fn map_marked(&self, letter: char, upper: bool, marks: &str) -> (String, String) {
+        map_marked(letter, upper, self.case, marks)
+    }
+}
+
+impl ClassMapping {
+    /// Returns true if the mapping applies to a character
+    fn matches(&self, c: char) -> bool {
+        check_chars(&self.patterns, &c) != self.complement
+    }
+}
+
+impl Targets {
+    /// Split string2 into runs. Ranges become the characters they cover and an
+    /// equivalence class can only translate into its base letter (or its own
+    /// character in byte mode). A repeat such as `[c*3]` becomes a run of that
+    /// many characters while `[c*]` fills string2 up to the length of string1,
+    /// whose members are counted up to `last` only when that is needed.
+    fn new(patterns: Vec<Pattern>, sources: &[Source], last: char, bytes: bool) -> Self {
+        let patterns = expand_ranges(patterns);
+
+        let fill = match patterns
+            .iter()
+            .any(|p| matches!(p, Pattern::Repeat(_, None)))
+        {
+            true => {
+                let len: usize = sources
+                    .iter()
+                    .map(|source| match source {
+                        Source::Char(_)
+                        | Source::Cluster(_)
+                        | Source::Set {
+                            class: Some(Pattern::Equivalence(_)),
+                            ..
+                        } => 1,
+                        Source::Set {
+                            patterns,
+                            complement,
+                            ..
+                        } => ('\0'..=last)
+                            .filter(|c| check_chars(patterns, c) != *complement)
+                            .count(),
+                    })
+                    .sum();
+                // A count can be as large as usize, so the sum saturates
+                let fixed = patterns
+                    .iter()
+                    .map(|p| match p {
+                        Pattern::Repeat(_, Some(count)) => *count,
+                        Pattern::Repeat(_, None) => 0,
+                        _ => 1,
+                    })
+                    .fold(0, usize::saturating_add);
+                len.saturating_sub(fixed)
+            }
+            false => 0,
+        };
+
+        let runs = patterns
+            .into_iter()
+            .map(|pattern| match pattern {
+                Pattern::Equivalence(e) if bytes => (Pattern::Char(e), 1),
+                Pattern::Equivalence(e) => (Pattern::Char(base_letter(e)), 1),
+                Pattern::Repeat(c, count) => (Pattern::Char(c), count.unwrap_or(fill)),
+                pattern => (pattern, 1),
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        Targets {
+            runs,
+            run: 0,
+            used: 0,
+        }
+    }
+
+    /// The number of positions left before string2 reaches its last pattern or
+    /// a class, which are the ones that pair with a single member of a set
+    fn singles(&self) -> usize {
+        let mut count: usize = 0;
+        for (i, (pattern, len)) in self.runs.iter().enumerate().skip(self.run) {
+            if i == self.runs.len() - 1
+                || !matches!(pattern, Pattern::Char(_) | Pattern::Cluster(_))
+            {
+                break;
+            }
+            count = count.saturating_add(match i == self.run {
+                true => len - self.used,
+                false => *len,
+            });
+        }
+
+        count
+    }
+
+    /// The pattern at the current position and whether it is the last one,
+    /// which pads string2 out to the length of string1
+    fn peek(&self) -> (&Pattern, bool) {
+        (&self.runs[self.run].0, self.run == self.runs.len() - 1)
+    }
+
+    /// Take the pattern at the current position and move on to the next one.
+    /// The last pattern is never used up.
+    fn next(&mut self) -> Pattern {
+        let (pattern, is_last) = self.peek();
+        let pattern = pattern.clone();
+
+        if !is_last {
+            self.used += 1;
+            if self.used == self.runs[self.run].1 {
+                self.run += 1;
+                self.used = 0;
+            }
+        }
+
+        pattern
+    }
+}
+
+/// Check that characters from `source` can be translated into `target`. Any
+/// character can be replaced by a character or have its case changed, but the
+/// only other classes string2 can hold are those that leave the characters
+/// paired with them as they are: a class paired with itself, or a class that
+/// a single character belongs to.
+fn check_target(source: &Pattern, target: &Pattern) -> Result<(), Error> {
+    let translatable = match (source, target) {
+        (_, Pattern::Char(_) | Pattern::Cluster(_) | Pattern::Lower | Pattern::Upper) => true,
+        (Pattern::Char(c), class) => check_char(class, c),
+        (Pattern::Cluster(cluster), class) => check_cluster(std::slice::from_ref(class), cluster),
+        (class, target) => class == target,
+    };
+
+    match translatable {
+        true => Ok(()),
+        false => Err(Error::Untranslatable {
+            from: source.clone(),
+            to: target.clone(),
+        }),
+    }
+}
+
+/// Translate a character into the pattern from string2 it is paired with. A
+/// class other than `[:lower:]` or `[:upper:]` leaves the character as it is.
+fn map_char(c: char, target: &Pattern, case: Case) -> Mapped {
+    let translated = match target {
+        Pattern::Char(new_c) => return Mapped::Char(*new_c),
+        Pattern::Cluster(cluster) => return Mapped::Chars(cluster.clone()),
+        Pattern::Lower => to_lower(c, case),
+        Pattern::Upper => to_upper(c, case),
+        _ => return Mapped::Char(c),
+    };
+
+    if case.mapping == CaseMapping::Full && is_marked(c, target, case.rules) {
+        return Mapped::Marked {
+            letter: c,
+            upper: matches!(target, Pattern::Upper),
+        };
+    }
+
+    // Whether a lowercase sigma ends a word depends on the characters around
+    // it, so that is left until the input is processed
+    let mut chars = translated.chars();
+    match (chars.next(), chars.next()) {
+        (Some('σ'), None) if c == 'Σ' && case.mapping == CaseMapping::Full => Mapped::Sigma,
+        (Some(new_c), None) => Mapped::Char(new_c),
+        (None, _) => Mapped::Char(c),
+        _ => Mapped::Chars(translated),
+    }
+}
+
+/// Translate an extended grapheme cluster into the pattern from string2 it is
+/// paired with. A character or cluster replaces the whole cluster, while
+/// `[:lower:]` and `[:upper:]` change the case of every character in it.
+fn map_cluster(cluster: &str, target: &Pattern, case: Case) -> Mapped {
+    let mut chars = cluster.chars();
+    if let Some(Mapped::Marked { letter, upper }) = chars.next().map(|c| map_char(c, target, case))
+    {
+        let (letter, marks) = map_marked(letter, upper, case, chars.as_str());
+        return Mapped::Chars(letter + &marks);
+    }
+
+    match target {
+        Pattern::Char(new_c) => Mapped::Char(*new_c),
+        Pattern::Cluster(new_cluster) => Mapped::Chars(new_cluster.clone()),
+        Pattern::Lower => Mapped::Chars(cluster.chars().map(|c| to_lower(c, case)).collect()),
+        Pattern::Upper => Mapped::Chars(cluster.chars().map(|c| to_upper(c, case)).collect()),
+        _ => Mapped::Chars(cluster.to_string()),
+    }
+}
+
+/// Change a character to uppercase. The full mapping can turn one character into
+/// several (`ß` into `SS`), while the simple mapping always gives one character
+/// and leaves characters that only have a full mapping as they are.
+fn to_upper(c: char, case: Case) -> String {
+    match (case.rules, c) {
+        (CaseRules::Turkic, 'i') => return "\u{130}".to_string(),
+        (CaseRules::Ascii, c) => return c.to_ascii_uppercase().to_string(),
+        _ => {}
+    }
+
+    let upper = c.to_uppercase();
+    if case.mapping == CaseMapping::Full || upper.len() == 1 {
+        return upper.collect();
+    }
+
+    // Greek letters with a subscript iota have a simple uppercase mapping to
+    // their titlecase form, which is eight code points on
+    let simple = match c {
+        '\u{1F80}'..='\u{1F87}' | '\u{1F90}'..='\u{1F97}' | '\u{1FA0}'..='\u{1FA7}' => {
+            char::from_u32(c as u32 + 8)
+        }
+        '\u{1FB3}' => Some('\u{1FBC}'),
+        '\u{1FC3}' => Some('\u{1FCC}'),
+        '\u{1FF3}' => Some('\u{1FFC}'),
+        _ => None,
+    };
+
+    simple.unwrap_or(c).to_string()
+}
+
+/// Change a character to lowercase. By default only `İ` has a full lowercase
+/// mapping of more than one character. In Lithuanian an `I` with a grave, acute
+/// or tilde keeps the dot of the `i` as a combining dot above, as does a letter
+/// followed by a combining accent (see `map_marked`).
+fn to_lower(c: char, case: Case) -> String {
+    let lower = match (case.rules, case.mapping, c) {
+        (CaseRules::Ascii, _, c) => return c.to_ascii_lowercase().to_string(),
+        (CaseRules::Turkic, _, 'I') => "\u{131}",
+        (CaseRules::Turkic, _, '\u{130}') => "i",
+        (CaseRules::Lithuanian, CaseMapping::Full, '\u{CC}') => "i\u{307}\u{300}",
+        (CaseRules::Lithuanian, CaseMapping::Full, '\u{CD}') => "i\u{307}\u{301}",
+        (CaseRules::Lithuanian, CaseMapping::Full, '\u{128}') => "i\u{307}\u{303}",
+        (_, CaseMapping::Simple, '\u{130}') => "i",
+        _ => return c.to_lowercase().collect(),
+    };
+
+    lower.to_string()
+}
+
+/// Returns true if the case mapping of a character into `[:lower:]` or
+/// `[:upper:]` depends on the combining marks after it
+fn is_marked(c: char, target: &Pattern, rules: CaseRules) -> bool {
+    match (rules, target) {
+        (CaseRules::Turkic, Pattern::Lower) => c == 'I',
+        (CaseRules::Lithuanian, Pattern::Lower) => matches!(c, 'I' | 'J' | '\u{12E}'),
+        (CaseRules::Lithuanian, Pattern::Upper) => SOFT_DOTTED.contains(&c),
+        _ => false,
+    }
+}
+
+/// The lowercase letters whose dot disappears under an accent placed above
+/// them, such as `i` and `j`
+const SOFT_DOTTED: &[char] = &[
+    'i', 'j', '\u{12F}', '\u{249}', '\u{268}', '\u{29D}', '\u{2B2}', '\u{3F3}', '\u{456}',
+    '\u{458}', '\u{1D62}', '\u{1D96}', '\u{1DA4}', '\u{1DA8}', '\u{1E2D}', '\u{1ECB}', '\u{2071}',
+    '\u{2148}', '\u{2149}', '\u{2C7C}',
+];
+
+/// Change the case of a letter using the combining marks that follow it, as the
+/// context rules of the Unicode special casings do. Returns the new letter and
+/// the marks to keep.
+///
+/// In Turkish and Azeri an `I` followed by a combining dot above lowercases to
+/// `i` and the dot goes. In Lithuanian an `I`, `J` or `Į` followed by an accent
+/// above keeps the dot of its lowercase form as a combining dot above, and when
+/// uppercasing a letter such as `i` the combining dot above that stood in for
+/// its dot goes.
+fn map_marked(letter: char, upper: bool, case: Case, marks: &str) -> (String, String) {
+    let dot = dot_above(marks);
+    let without_dot = |i: usize| format!("{}{}", &marks[..i], &marks[i + '\u{307}'.len_utf8()..]);
+
+    match (case.rules, upper, dot) {
+        (CaseRules::Turkic, false, Some(i)) => ("i".to_string(), without_dot(i)),
+        (CaseRules::Lithuanian, true, Some(i)) => (to_upper(letter, case), without_dot(i)),
+        (CaseRules::Lithuanian, false, _)
+            if marks.chars().any(|m| canonical_combining_class(m) == 230) =>
+        {
+            (to_lower(letter, case) + "\u{307}", marks.to_string())
+        }
+        (_, true, _) => (to_upper(letter, case), marks.to_string()),
+        (_, false, _) => (to_lower(letter, case), marks.to_string()),
+    }
+}
+
+/// Find the combining dot above in the marks after a letter, unless another
+/// accent above comes first
+fn dot_above(marks: &str) -> Option<usize> {
+    for (i, m) in marks.char_indices() {
+        match m {
+            '\u{307}' => return Some(i),
+            m if canonical_combining_class(m) == 230 => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Returns true if a character has case, which is how a word is found around a
+/// sigma when choosing its final form
+pub(crate) fn is_cased(c: char) -> bool {
+    c.is_lowercase() || c.is_uppercase() || is_titlecase(c)
+}
+
+/// Returns true for the titlecase letters, such as `ǅ`
+fn is_titlecase(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1C5}'
+            | '\u{1C8}'
+            | '\u{1CB}'
+            | '\u{1F2}'
+            | '\u{1F88}'..='\u{1F8F}'
+            | '\u{1F98}'..='\u{1F9F}'
+            | '\u{1FA8}'..='\u{1FAF}'
+            | '\u{1FBC}'
+            | '\u{1FCC}'
+            | '\u{1FFC}'
+    )
+}
+
+/// Returns true for characters that are skipped when looking for the letters
+/// around a sigma, such as accents and apostrophes
+pub(crate) fn is_case_ignorable(c: char) -> bool {
+    is_combining_mark(c)
+        || matches!(
+            c,
+            '\'' | '.'
+                | ':'
+                | '^'
+                | '`'
+                | '\u{AD}'
+                | '\u{B7}'
+                | '\u{2018}'
+                | '\u{2019}'
+                | '\u{2024}'
+                | '\u{2027}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{FEFF}'
+        )
+}
+
+/// Spell a character in ASCII, or return `None` if it has no ASCII spelling.
+/// Letters lose their accents, compatibility characters such as `ﬁ` and `½`
+/// are spelled out, and combining marks on their own become nothing.
+pub(crate) fn to_ascii(c: char) -> Option<String> {
+    if c.is_ascii() {
+        return Some(c.to_string());
+    }
+    if let Some(spelling) = ascii_spelling(c) {
+        return Some(spelling.to_string());
+    }
+
+    let mut ascii = String::new();
+    for d in std::iter::once(c).nfkd() {
+        match d.is_ascii() {
+            true => ascii.push(d),
+            false if is_combining_mark(d) => (),
+            false => ascii.push_str(ascii_spelling(d)?),
+        }
+    }
+
+    Some(ascii)
+}
+
+/// The ASCII spellings of the letters, ligatures and symbols that do not
+/// decompose into ASCII
+fn ascii_spelling(c: char) -> Option<&'static str> {
+    let spelling = match c {
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'ß' => "ss",
+        'ẞ' => "SS",
+        'Ø' => "O",
+        'ø' => "o",
+        'Đ' | 'Ð' => "D",
+        'đ' | 'ð' => "d",
+        'Þ' => "TH",
+        'þ' => "th",
+        'Ł' => "L",
+        'ł' => "l",
+        'Ħ' => "H",
+        'ħ' => "h",
+        'ı' => "i",
+        'Ŋ' => "NG",
+        'ŋ' => "ng",
+        'Ŧ' => "T",
+        'ŧ' => "t",
+        'ƒ' => "f",
+        '€' => "EUR",
+        '£' => "GBP",
+        '¥' => "JPY",
+        '¢' => "c",
+        '©' => "(C)",
+        '®' => "(R)",
+        '±' => "+/-",
+        '×' => "x",
+        '÷' | '⁄' => "/",
+        '¡' => "!",
+        '¿' => "?",
+        '¦' => "|",
+        '«' => "<<",
+        '»' => ">>",
+        '‹' => "<",
+        '›' => ">",
+        '•' => "*",
+        '‘' | '’' | '‚' | '‛' | '′' => "'",
+        '“' | '”' | '„' | '‟' | '″' => "\"",
+        '‐' | '‒' | '–' | '—' | '―' | '−' => "-",
+        _ => return None,
+    };
+
+    Some(spelling)
+}
+
+/// The first `count` members of a set in collation sequence, followed by one
+/// more member if there is one. The members after those are not paired one by
+/// one, so their order does not matter.
+fn collated(members: impl Iterator<Item = char>, count: usize) -> Vec<char> {
+    if count == 0 {
+        return members.take(1).collect();
+    }
+
+    let keep = count.saturating_add(1);
+    let mut members: Vec<_> = members.map(|c| (collation_key(c), c)).collect();
+    if keep < members.len() {
+        members.select_nth_unstable(keep);
+        members.truncate(keep);
+    }
+    members.sort_unstable();
+
+    members.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Where a character comes in the collation sequence. As in the default
+/// Unicode collation, controls come first, then spaces, punctuation and
+/// symbols, digits and letters. Letters sort by their base letter, with the
+/// plain letter before accented ones and lowercase before uppercase. Anything
+/// else keeps its code point order.
+fn collation_key(c: char) -> (u8, u32, bool, bool, u32) {
+    let group = if c.is_control() {
+        0
+    } else if c.is_whitespace() {
+        1
+    } else if is_punct(c) {
+        2
+    } else if c.is_numeric() {
+        3
+    } else if c.is_alphabetic() {
+        4
+    } else {
+        5
+    };
+
+    match group {
+        3 => (
+            group,
+            c.to_digit(10).unwrap_or(c as u32),
+            false,
+            false,
+            c as u32,
+        ),
+        4 => {
+            let base = base_letter(c);
+            let primary = base.to_lowercase().next().unwrap_or(base);
+            (group, primary as u32, base != c, c.is_uppercase(), c as u32)
+        }
+        _ => (group, c as u32, false, false, c as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ************************************************************************
+    // collation tests
+    // ************************************************************************
+
+    #[test]
+    fn collation_puts_letters_alphabetically_after_other_characters() {
+        let mut chars = vec!['b', 'B', 'é', 'E', 'e', 'a', '1', '!', ' ', '\0'];
+        chars.sort_by_key(|c| collation_key(*c));
+
+        assert_eq!(chars, ['\0', ' ', '!', '1', 'a', 'b', 'B', 'e', 'E', 'é']);
+    }
+
+    // ************************************************************************
+    // transliteration tests
+    // ************************************************************************
+
+    #[test]
+    fn to_ascii_spells_letters_ligatures_and_symbols() {
+        assert_eq!(to_ascii('é').as_deref(), Some("e"));
+        assert_eq!(to_ascii('Ñ').as_deref(), Some("N"));
+        assert_eq!(to_ascii('æ').as_deref(), Some("ae"));
+        assert_eq!(to_ascii('ß').as_deref(), Some("ss"));
+        assert_eq!(to_ascii('€').as_deref(), Some("EUR"));
+        assert_eq!(to_ascii('ﬁ').as_deref(), Some("fi"));
+        assert_eq!(to_ascii('½').as_deref(), Some("1/2"));
+        assert_eq!(to_ascii('\u{301}').as_deref(), Some(""));
+        assert_eq!(to_ascii('中'), None);
+    }
+}
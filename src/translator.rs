@@ -0,0 +1,1412 @@
+pub use crate::args::{AsciiFallback, CaseMapping, CaseRules, InvalidUtf8, Normalization};
+use crate::error::{Error, Operand};
+use crate::init::Mode;
+use crate::set::{
+    byte_patterns, check_bytes, check_chars, check_cluster, group_graphemes, has_cluster,
+    literal_bytes, normalize, normalize_set, parse, Pattern,
+};
+use crate::table::{is_case_ignorable, is_cased, to_ascii, Case, Mapped, TranslationTable};
+
+use log::debug;
+use std::borrow::Cow;
+use std::io::{ErrorKind, Read, Write};
+use unicode_normalization::char::{canonical_combining_class, is_combining_mark};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The number of bytes read from the input at a time
+pub(crate) const CHUNK_SIZE: usize = 8 * 1024;
+
+/// string1 and string2 compiled for a mode. The sets are parsed once and then
+/// applied to every character of the input.
+///
+/// ```
+/// use cctr::translator::Translator;
+///
+/// let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+///
+/// assert_eq!(translator.apply("coding challenge"), "CODING CHALLENGE");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Translator {
+    /// What string1 translates into, when translating
+    table: Option<TranslationTable>,
+    /// The characters to delete
+    delete: Option<Set>,
+    /// Also delete the combining marks that follow a deleted character
+    delete_marks: bool,
+    /// The characters to squeeze
+    squeeze: Option<Set>,
+    /// Treat every byte of the input as a character
+    bytes: bool,
+    /// Treat every extended grapheme cluster of the input as a character
+    graphemes: bool,
+    /// The normalization form to bring the input into before matching
+    normalize: Option<Normalization>,
+    /// The normalization form to bring the output into
+    normalize_output: Option<Normalization>,
+    /// How to spell the input in ASCII before anything else happens to it
+    ascii: Option<Transliteration>,
+    /// What to do with input that is not UTF-8
+    invalid_utf8: InvalidUtf8,
+}
+
+/// Collects the sets and options for a `Translator`. The options match the
+/// flags of the command line tool.
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    string1: String,
+    string2: Option<String>,
+    complement: bool,
+    collate: bool,
+    delete: bool,
+    squeeze: bool,
+    delete_marks: bool,
+    bytes: bool,
+    graphemes: bool,
+    normalize: Option<Normalization>,
+    normalize_output: Option<Normalization>,
+    ascii: bool,
+    ascii_fallback: AsciiFallback,
+    invalid_utf8: InvalidUtf8,
+    case_mapping: CaseMapping,
+    case_rules: CaseRules,
+}
+
+impl Builder {
+    /// The set to translate into. Without `delete` or `squeeze` it is required.
+    pub fn string2(mut self, string2: impl Into<String>) -> Self {
+        self.string2 = Some(string2.into());
+        self
+    }
+
+    /// Use every character string1 does not match in place of string1
+    pub fn complement(mut self, complement: bool) -> Self {
+        self.complement = complement;
+        self
+    }
+
+    /// Put the complement of string1 in collation sequence rather than code
+    /// point order when it pairs with string2, as POSIX does for `-c`. This has
+    /// no effect in byte mode, where the two orders are the same.
+    pub fn collate(mut self, collate: bool) -> Self {
+        self.collate = collate;
+        self
+    }
+
+    /// Delete the characters in string1 rather than translating them
+    pub fn delete(mut self, delete: bool) -> Self {
+        self.delete = delete;
+        self
+    }
+
+    /// Squeeze runs of the characters in the last set given into one
+    pub fn squeeze(mut self, squeeze: bool) -> Self {
+        self.squeeze = squeeze;
+        self
+    }
+
+    /// Also delete the combining marks that follow a deleted character, which
+    /// would otherwise attach themselves to the character before it
+    pub fn delete_marks(mut self, delete_marks: bool) -> Self {
+        self.delete_marks = delete_marks;
+        self
+    }
+
+    /// Treat every byte of the input as a character rather than decoding UTF-8
+    pub fn bytes(mut self, bytes: bool) -> Self {
+        self.bytes = bytes;
+        self
+    }
+
+    /// Treat extended grapheme clusters, such as an `e` followed by a combining
+    /// accent or a flag emoji, as single characters in the sets and the input.
+    /// This has no effect in byte mode.
+    pub fn graphemes(mut self, graphemes: bool) -> Self {
+        self.graphemes = graphemes;
+        self
+    }
+
+    /// Normalize the input, and the characters in the sets, before matching.
+    /// Ranges are not normalized, and a set character that normalizes into
+    /// several, such as `ﬁ` under NFKC, is an error. This has no effect in
+    /// byte mode.
+    pub fn normalize(mut self, form: Option<Normalization>) -> Self {
+        self.normalize = form;
+        self
+    }
+
+    /// Normalize the output. This has no effect in byte mode.
+    pub fn normalize_output(mut self, form: Option<Normalization>) -> Self {
+        self.normalize_output = form;
+        self
+    }
+
+    /// Transliterate the input into ASCII before deleting, translating and
+    /// squeezing it. Without `string2`, `delete` or `squeeze` only the
+    /// characters in string1 are transliterated.
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// What to do with the characters that have no ASCII spelling
+    pub fn ascii_fallback(mut self, fallback: AsciiFallback) -> Self {
+        self.ascii_fallback = fallback;
+        self
+    }
+
+    /// What to do with input that is not UTF-8 when not in byte mode
+    pub fn invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> Self {
+        self.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    /// Use full or simple case mappings when translating into `[:lower:]` or
+    /// `[:upper:]`
+    pub fn case_mapping(mut self, case_mapping: CaseMapping) -> Self {
+        self.case_mapping = case_mapping;
+        self
+    }
+
+    /// Use the case rules of a language, such as Turkish where the capital of
+    /// `i` is `İ`
+    pub fn case_rules(mut self, case_rules: CaseRules) -> Self {
+        self.case_rules = case_rules;
+        self
+    }
+
+    /// Check the options go together and compile the sets
+    pub fn build(&self) -> Result<Translator, Error> {
+        let mode = match Mode::new(
+            self.string2.is_some(),
+            self.delete,
+            self.squeeze,
+            self.ascii,
+        ) {
+            Some(mode) => mode,
+            None if self.string2.is_some() => return Err(Error::IncompatibleFlags),
+            None => return Err(Error::MissingString2),
+        };
+
+        self.compile(&mode)
+    }
+
+    /// Compile the sets for a mode that has already been worked out
+    pub(crate) fn compile(&self, mode: &Mode) -> Result<Translator, Error> {
+        // Extract a list of patterns to process from string1
+        let mut set1 = parse(&self.string1, Operand::String1)?;
+
+        if let Some((_, span)) = set1.iter().find(|(p, _)| matches!(p, Pattern::Repeat(..))) {
+            return Err(Error::MisplacedRepeat {
+                operand: Operand::String1,
+                repeat: self.string1[span.clone()].to_string(),
+                offset: span.start,
+            });
+        }
+
+        // Extract a list of patterns to process from string2
+        // (only if in Translate, TranslateCompress or DeleteCompress mode)
+        let string2 = self.string2.clone().unwrap_or_default();
+        let mut set2 = match mode {
+            Mode::Translate | Mode::TranslateCompress | Mode::DeleteCompress => {
+                parse(&string2, Operand::String2)?
+            }
+            Mode::Delete | Mode::Compress | Mode::Transliterate => Vec::new(),
+        };
+
+        // Only one repeat can fill string2 up to the length of string1
+        if let Some((_, span)) = set2
+            .iter()
+            .filter(|(p, _)| matches!(p, Pattern::Repeat(_, None)))
+            .nth(1)
+        {
+            return Err(Error::MisplacedRepeat {
+                operand: Operand::String2,
+                repeat: string2[span.clone()].to_string(),
+                offset: span.start,
+            });
+        }
+
+        // There must be something in string2 to translate into
+        if set2.is_empty() && matches!(mode, Mode::Translate | Mode::TranslateCompress) {
+            return Err(Error::EmptyString2);
+        }
+
+        // Clusters and normalization forms are made of characters, which byte
+        // mode does not have
+        let graphemes = self.graphemes && !self.bytes;
+        let (normalize, normalize_output) = match self.bytes {
+            true => (None, None),
+            false => (self.normalize, self.normalize_output),
+        };
+
+        // The sets have to be written the same way as the input to match it
+        if let Some(form) = normalize {
+            set1 = normalize_set(set1, form, &self.string1, Operand::String1)?;
+            set2 = normalize_set(set2, form, &string2, Operand::String2)?;
+        }
+
+        if graphemes {
+            set1 = group_graphemes(set1);
+            set2 = group_graphemes(set2);
+        }
+
+        if self.bytes {
+            set1 = literal_bytes(set1, &self.string1);
+            set2 = literal_bytes(set2, &string2);
+            check_bytes(&set1, &self.string1, Operand::String1)?;
+            check_bytes(&set2, &string2, Operand::String2)?;
+        }
+
+        let table1: Vec<Pattern> = set1.into_iter().map(|(p, _)| p).collect();
+        let table2: Vec<Pattern> = set2.into_iter().map(|(p, _)| p).collect();
+
+        // In byte mode the sets only hold byte values. The translation table
+        // restricts them itself, so that a class in string1 still pairs up with
+        // a class in string2.
+        let (patterns1, patterns2) = match self.bytes {
+            true => (byte_patterns(table1.clone()), byte_patterns(table2.clone())),
+            false => (table1.clone(), table2.clone()),
+        };
+
+        debug!("Mode: {:?}", mode);
+        debug!(
+            "string1 compiled to {}{}",
+            describe(&patterns1),
+            match self.complement {
+                true => " (complemented)",
+                false => "",
+            }
+        );
+        if !patterns2.is_empty() {
+            debug!("string2 compiled to {}", describe(&patterns2));
+        }
+
+        // Characters are transliterated first, so that the sets only need to
+        // hold ASCII. On its own only the characters in string1 are
+        // transliterated, otherwise all of them are.
+        let complement = self.complement;
+        let collate = self.collate;
+        let ascii = match mode {
+            Mode::Transliterate => Some(Transliteration {
+                set: Some(Set::new(patterns1.clone(), complement)),
+                fallback: self.ascii_fallback,
+            }),
+            _ if self.ascii => Some(Transliteration {
+                set: None,
+                fallback: self.ascii_fallback,
+            }),
+            _ => None,
+        };
+
+        // Then characters are deleted, translated and squeezed. When there are
+        // two strings the characters in string2 are squeezed.
+        let case = Case {
+            mapping: self.case_mapping,
+            // Only ASCII letters have case in byte mode, as in the C locale
+            rules: match self.bytes {
+                true => CaseRules::Ascii,
+                false => self.case_rules,
+            },
+        };
+        let (table, delete, squeeze) = match mode {
+            Mode::Translate => (
+                Some(TranslationTable::new(
+                    table1, table2, complement, collate, case, self.bytes,
+                )?),
+                None,
+                None,
+            ),
+            Mode::TranslateCompress => (
+                Some(TranslationTable::new(
+                    table1, table2, complement, collate, case, self.bytes,
+                )?),
+                None,
+                Some(Set::new(patterns2, false)),
+            ),
+            Mode::Delete => (None, Some(Set::new(patterns1, complement)), None),
+            Mode::Compress => (None, None, Some(Set::new(patterns1, complement))),
+            Mode::DeleteCompress => (
+                None,
+                Some(Set::new(patterns1, complement)),
+                Some(Set::new(patterns2, false)),
+            ),
+            Mode::Transliterate => (None, None, None),
+        };
+
+        Ok(Translator {
+            table,
+            delete,
+            squeeze,
+            delete_marks: self.delete_marks,
+            bytes: self.bytes,
+            graphemes,
+            normalize,
+            normalize_output,
+            ascii,
+            invalid_utf8: self.invalid_utf8,
+        })
+    }
+}
+
+impl Translator {
+    /// Start building a translator from string1
+    pub fn builder(string1: impl Into<String>) -> Builder {
+        Builder {
+            string1: string1.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Translate, delete and squeeze a string. The string is returned as it is
+    /// when nothing in it changes.
+    ///
+    /// In byte mode the UTF-8 bytes of the string are processed, just as
+    /// `apply_bytes` would, so a set written as `é` matches the two bytes of an
+    /// `é`. Any bytes that no longer make up a UTF-8 character afterwards are
+    /// replaced with U+FFFD.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        // Writing to a Vec cannot fail and the text is valid UTF-8
+        let output = self
+            .apply_bytes(text.as_bytes())
+            .expect("a string is valid UTF-8");
+
+        match output {
+            Cow::Borrowed(_) => Cow::Borrowed(text),
+            Cow::Owned(bytes) => match String::from_utf8(bytes) {
+                Ok(output) => Cow::Owned(output),
+                Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+            },
+        }
+    }
+
+    /// Translate, delete and squeeze a slice of bytes. Unless the translator is
+    /// in byte mode the bytes are decoded as UTF-8 first.
+    pub fn apply_bytes<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, [u8]>, Error> {
+        let mut output = Vec::with_capacity(bytes.len());
+        let mut stream = Stream::default();
+        stream.write(self, bytes, &mut output)?;
+        stream.finish(self, &mut output)?;
+
+        match output == bytes {
+            true => Ok(Cow::Borrowed(bytes)),
+            false => Ok(Cow::Owned(output)),
+        }
+    }
+
+    /// Read `reader` to the end a chunk at a time and write the result to
+    /// `writer`. Each processed chunk is written as soon as it is ready, so wrap
+    /// the writer in a `BufWriter` to buffer the output.
+    pub fn process(&self, mut reader: impl Read, mut writer: impl Write) -> Result<(), Error> {
+        let mut stream = Stream::default();
+        let mut chunk = vec![0; CHUNK_SIZE];
+
+        loop {
+            let len = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(Error::Io(e)),
+            };
+
+            stream.write(self, &chunk[..len], &mut writer)?;
+        }
+
+        stream.finish(self, &mut writer)?;
+        writer.flush().map_err(Error::Io)
+    }
+
+    /// Delete, translate and squeeze a piece of text, adding the result to
+    /// `output`. In grapheme mode, or when normalizing, the last cluster is held
+    /// back in `state`, as the text that follows may add to it.
+    fn process_text(&self, text: &str, state: &mut Stream, output: &mut String) {
+        if !self.graphemes && self.normalize.is_none() && self.normalize_output.is_none() {
+            return self.process_chars(text.chars(), state, output);
+        }
+
+        // Nothing can be added to a line feed, so a line is never held back
+        let mut text = std::mem::take(&mut state.tail) + text;
+        let tail = match text.ends_with('\n') {
+            true => text.len(),
+            false => text
+                .grapheme_indices(true)
+                .next_back()
+                .map_or(0, |(i, _)| i),
+        };
+        state.tail = text.split_off(tail);
+
+        self.process_clusters(&text, state, output);
+    }
+
+    /// Delete, translate and squeeze text that ends on a cluster boundary,
+    /// normalizing it on the way in and out when asked to
+    fn process_clusters(&self, text: &str, state: &mut Stream, output: &mut String) {
+        let text = match self.normalize {
+            Some(form) => Cow::Owned(normalize(text, form)),
+            None => Cow::Borrowed(text),
+        };
+
+        let mut processed = String::new();
+        for cluster in text.graphemes(true) {
+            // Outside grapheme mode a cluster is only handled as a whole when a
+            // set holds it, which happens when normalizing splits a character
+            // in a set into several
+            match self.graphemes || self.has_cluster(cluster) {
+                true => self.process_cluster(cluster, state, &mut processed),
+                false => self.process_chars(cluster.chars(), state, &mut processed),
+            }
+        }
+
+        self.push_output(&processed, output);
+    }
+
+    /// Returns true if one of the sets holds an extended grapheme cluster as a
+    /// whole
+    fn has_cluster(&self, cluster: &str) -> bool {
+        self.table
+            .as_ref()
+            .is_some_and(|table| table.has_cluster(cluster))
+            || [&self.delete, &self.squeeze]
+                .into_iter()
+                .flatten()
+                .any(|set| has_cluster(&set.patterns, cluster))
+    }
+
+    /// Add processed text to the output, normalizing it when asked to
+    fn push_output(&self, processed: &str, output: &mut String) {
+        match self.normalize_output {
+            Some(form) => output.push_str(&normalize(processed, form)),
+            None => output.push_str(processed),
+        }
+    }
+
+    /// Delete, translate and squeeze `chars`, adding the result to `output`.
+    /// `state` holds the last character added so that a run of characters is
+    /// squeezed even when it is split between chunks, as well as a count of what
+    /// happened to the characters.
+    fn process_chars(
+        &self,
+        chars: impl Iterator<Item = char>,
+        state: &mut Stream,
+        output: &mut String,
+    ) {
+        for c in chars {
+            // A letter whose case depends on the marks after it waits for them
+            if let Some(marked) = &mut state.marked {
+                if canonical_combining_class(c) != 0 {
+                    marked.marks.push(c);
+                    continue;
+                }
+
+                self.resolve_marked(state, output);
+            }
+
+            // A sigma ends a word unless a letter follows it, skipping over
+            // accents and the like
+            if let Some(sigma) = &mut state.sigma {
+                if is_case_ignorable(c) {
+                    sigma.ignorable.push(c);
+                    continue;
+                }
+
+                self.resolve_sigma(!is_cased(c), state, output);
+            }
+
+            self.process_char(c, state, output);
+        }
+    }
+
+    /// Handle the end of the characters, which ends the last cluster and any
+    /// word a sigma is in
+    fn end_chars(&self, state: &mut Stream, output: &mut String) {
+        let tail = std::mem::take(&mut state.tail);
+        if !tail.is_empty() {
+            self.process_clusters(&tail, state, output);
+        }
+
+        if state.marked.is_some() || state.sigma.is_some() {
+            let mut processed = String::new();
+            self.resolve_marked(state, &mut processed);
+            self.resolve_sigma(true, state, &mut processed);
+            self.push_output(&processed, output);
+        }
+    }
+
+    /// Transliterate, delete, translate and squeeze a single character
+    fn process_char(&self, c: char, state: &mut Stream, output: &mut String) {
+        state.counts.read += 1;
+
+        match self.ascii.as_ref().and_then(|ascii| ascii.spell(c)) {
+            Some(spelling) => {
+                state.counts.transliterated += 1;
+                for c in spelling.chars() {
+                    self.translate_char(c, state, output);
+                }
+            }
+            None => self.translate_char(c, state, output),
+        }
+    }
+
+    /// Delete, translate and squeeze a single character
+    fn translate_char(&self, c: char, state: &mut Stream, output: &mut String) {
+        let cased = state.cased;
+        if is_cased(c) {
+            state.cased = true;
+        } else if !is_case_ignorable(c) {
+            state.cased = false;
+        }
+
+        // A combining mark after a deleted character has nothing left to sit on
+        let orphaned = state.orphaned && is_combining_mark(c);
+        if orphaned || self.delete.as_ref().is_some_and(|set| set.contains(c)) {
+            state.counts.deleted += 1;
+            state.orphaned = self.delete_marks;
+            return;
+        }
+        state.orphaned = false;
+
+        let mapped = match &self.table {
+            Some(table) => table.get(c),
+            None => Mapped::Char(c),
+        };
+
+        match mapped {
+            Mapped::Char(new_c) => {
+                state.counts.translated += usize::from(new_c != c);
+                self.push_char(new_c, state, output);
+            }
+            Mapped::Chars(new_chars) => {
+                state.counts.translated += 1;
+                for new_c in new_chars.chars() {
+                    self.push_char(new_c, state, output);
+                }
+            }
+            Mapped::Sigma => {
+                state.counts.translated += 1;
+                state.sigma = Some(Sigma {
+                    cased,
+                    ignorable: String::new(),
+                });
+            }
+            Mapped::Marked { letter, upper } => {
+                state.counts.translated += 1;
+                state.marked = Some(Marked {
+                    letter,
+                    upper,
+                    marks: String::new(),
+                });
+            }
+        }
+    }
+
+    /// Delete, translate and squeeze an extended grapheme cluster as a whole.
+    /// A cluster of one character is handled like any other character.
+    fn process_cluster(&self, cluster: &str, state: &mut Stream, output: &mut String) {
+        let mut chars = cluster.chars();
+        let (Some(first), Some(_)) = (chars.next(), chars.next()) else {
+            return self.process_chars(cluster.chars(), state, output);
+        };
+
+        self.resolve_marked(state, output);
+        if state.sigma.is_some() {
+            self.resolve_sigma(!is_cased(first), state, output);
+        }
+
+        // A cluster spelled in ASCII is no longer a cluster
+        if let Some(spelling) = self.ascii.as_ref().and_then(|a| a.spell_cluster(cluster)) {
+            state.counts.read += 1;
+            state.counts.transliterated += 1;
+            for c in spelling.chars() {
+                self.translate_char(c, state, output);
+            }
+            return;
+        }
+
+        state.counts.read += 1;
+        for c in cluster.chars() {
+            if is_cased(c) {
+                state.cased = true;
+            } else if !is_case_ignorable(c) {
+                state.cased = false;
+            }
+        }
+
+        if self
+            .delete
+            .as_ref()
+            .is_some_and(|set| set.contains_cluster(cluster))
+        {
+            state.counts.deleted += 1;
+            state.orphaned = self.delete_marks;
+            return;
+        }
+        state.orphaned = false;
+
+        match self
+            .table
+            .as_ref()
+            .and_then(|table| table.get_cluster(cluster))
+        {
+            Some(Mapped::Char(new_c)) => {
+                state.counts.translated += 1;
+                self.push_char(new_c, state, output);
+            }
+            Some(Mapped::Chars(new_cluster)) => {
+                state.counts.translated += usize::from(new_cluster != cluster);
+                self.push_cluster(&new_cluster, state, output);
+            }
+            Some(Mapped::Sigma | Mapped::Marked { .. }) | None => {
+                self.push_cluster(cluster, state, output)
+            }
+        }
+    }
+
+    /// Write a lowercase sigma that was waiting for the end of its word to be
+    /// found, followed by the characters read after it
+    fn resolve_sigma(&self, word_ends: bool, state: &mut Stream, output: &mut String) {
+        let Some(sigma) = state.sigma.take() else {
+            return;
+        };
+
+        let c = match sigma.cased && word_ends {
+            true => 'ς',
+            false => 'σ',
+        };
+        self.push_char(c, state, output);
+
+        for c in sigma.ignorable.chars() {
+            self.process_char(c, state, output);
+        }
+    }
+
+    /// Write a letter that was waiting for the combining marks after it, followed
+    /// by the marks its case mapping keeps
+    fn resolve_marked(&self, state: &mut Stream, output: &mut String) {
+        let (Some(marked), Some(table)) = (state.marked.take(), &self.table) else {
+            return;
+        };
+
+        let (letter, marks) = table.map_marked(marked.letter, marked.upper, &marked.marks);
+        for c in letter.chars() {
+            self.push_char(c, state, output);
+        }
+
+        // A dot above that the mapping took away counts as translated
+        let dropped = marked.marks.chars().count() - marks.chars().count();
+        state.counts.read += dropped;
+        state.counts.translated += dropped;
+
+        for c in marks.chars() {
+            self.process_char(c, state, output);
+        }
+    }
+
+    /// Add a translated character to the output unless it is squeezed
+    fn push_char(&self, c: char, state: &mut Stream, output: &mut String) {
+        if state.last == Some(c) && self.squeeze.as_ref().is_some_and(|set| set.contains(c)) {
+            state.counts.squeezed += 1;
+            return;
+        }
+
+        output.push(c);
+        state.last = Some(c);
+        state.cluster = None;
+    }
+
+    /// Add a translated cluster to the output unless it is squeezed
+    fn push_cluster(&self, cluster: &str, state: &mut Stream, output: &mut String) {
+        if state.cluster.as_deref() == Some(cluster)
+            && self
+                .squeeze
+                .as_ref()
+                .is_some_and(|set| set.contains_cluster(cluster))
+        {
+            state.counts.squeezed += 1;
+            return;
+        }
+
+        output.push_str(cluster);
+        state.last = None;
+        state.cluster = Some(cluster.to_string());
+    }
+}
+
+/// List the patterns of a compiled set the way they would be written
+fn describe(patterns: &[Pattern]) -> String {
+    let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+
+    format!("[{}]", patterns.join(", "))
+}
+
+/// A set of characters to delete or squeeze
+#[derive(Debug, Clone)]
+struct Set {
+    /// The patterns in the set
+    patterns: Vec<Pattern>,
+    /// True if the set holds every character the patterns do not match
+    complement: bool,
+}
+
+impl Set {
+    fn new(patterns: Vec<Pattern>, complement: bool) -> Set {
+        Set {
+            patterns,
+            complement,
+        }
+    }
+
+    /// Returns true if a character is in the set
+    fn contains(&self, c: char) -> bool {
+        check_chars(&self.patterns, &c) != self.complement
+    }
+
+    /// Returns true if an extended grapheme cluster is in the set
+    fn contains_cluster(&self, cluster: &str) -> bool {
+        check_cluster(&self.patterns, cluster) != self.complement
+    }
+}
+
+/// The characters to spell in ASCII and what to do with the ones that have no
+/// ASCII spelling
+#[derive(Debug, Clone)]
+struct Transliteration {
+    /// The characters to transliterate, or `None` for every character
+    set: Option<Set>,
+    /// What to do with a character that has no ASCII spelling
+    fallback: AsciiFallback,
+}
+
+impl Transliteration {
+    /// Returns what a character is spelled as, or `None` if it is left as it is
+    fn spell(&self, c: char) -> Option<String> {
+        if c.is_ascii() || self.set.as_ref().is_some_and(|set| !set.contains(c)) {
+            return None;
+        }
+
+        self.spell_char(c)
+    }
+
+    /// Returns what an extended grapheme cluster is spelled as, or `None` if it
+    /// is left as it is
+    fn spell_cluster(&self, cluster: &str) -> Option<String> {
+        if cluster.is_ascii()
+            || self
+                .set
+                .as_ref()
+                .is_some_and(|set| !set.contains_cluster(cluster))
+        {
+            return None;
+        }
+
+        let mut spelling = String::new();
+        for c in cluster.chars() {
+            match self.spell_char(c) {
+                Some(ascii) => spelling.push_str(&ascii),
+                None => spelling.push(c),
+            }
+        }
+
+        (spelling != cluster).then_some(spelling)
+    }
+
+    /// Spell a character in ASCII, falling back when it has no ASCII spelling
+    fn spell_char(&self, c: char) -> Option<String> {
+        match (to_ascii(c), self.fallback) {
+            (Some(ascii), _) => Some(ascii),
+            (None, AsciiFallback::Replace) => Some("?".to_string()),
+            (None, AsciiFallback::Drop) => Some(String::new()),
+            (None, AsciiFallback::Keep) => None,
+        }
+    }
+}
+
+/// How many characters were read and what happened to them
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct Counts {
+    pub(crate) read: usize,
+    pub(crate) transliterated: usize,
+    pub(crate) translated: usize,
+    pub(crate) deleted: usize,
+    pub(crate) squeezed: usize,
+}
+
+/// A letter waiting for the combining marks after it to be read
+#[derive(Debug)]
+struct Marked {
+    /// The letter as it was read
+    letter: char,
+    /// True if the letter is uppercased, false if it is lowercased
+    upper: bool,
+    /// The combining marks read since the letter
+    marks: String,
+}
+
+/// A lowercase sigma waiting for the character after it
+#[derive(Debug)]
+struct Sigma {
+    /// True if a letter with case comes before the sigma
+    cased: bool,
+    /// The accents and the like read since the sigma
+    ignorable: String,
+}
+
+/// What needs to be carried over from one chunk of input to the next when
+/// running a stream through a `Translator`
+#[derive(Debug, Default)]
+pub(crate) struct Stream {
+    /// The last character written, so that squeezing carries on across chunks
+    last: Option<char>,
+    /// The last cluster written in grapheme mode, when it is more than one
+    /// character
+    cluster: Option<String>,
+    /// The last cluster read in grapheme mode, which the next chunk may add to
+    tail: String,
+    /// True when the last character read was deleted and the combining marks
+    /// after it are to be deleted too
+    orphaned: bool,
+    /// What has happened to the characters so far
+    pub(crate) counts: Counts,
+    /// True if the input so far ends in a letter that has case, followed by
+    /// any accents and the like
+    cased: bool,
+    /// A lowercase sigma that cannot be written until it is known whether it
+    /// ends a word
+    sigma: Option<Sigma>,
+    /// A letter whose case mapping cannot be written until the combining marks
+    /// after it are known
+    marked: Option<Marked>,
+    /// The start of a UTF-8 sequence that was cut off at the end of a chunk
+    pending: Vec<u8>,
+    /// The number of bytes of input before `pending`
+    offset: usize,
+}
+
+impl Stream {
+    /// Process the next chunk of input and write the result to `writer`
+    pub(crate) fn write(
+        &mut self,
+        translator: &Translator,
+        chunk: &[u8],
+        mut writer: impl Write,
+    ) -> Result<(), Error> {
+        if translator.bytes {
+            // Every byte stands for the character with the same value, so
+            // `\351` matches the byte 0xE9. Byte mode sets only hold bytes, so
+            // every character written is a byte too.
+            let mut output = String::new();
+            let chars = chunk.iter().map(|b| char::from(*b));
+            translator.process_chars(chars, self, &mut output);
+
+            let bytes: Vec<u8> = output
+                .chars()
+                .map(|c| u8::try_from(c).expect("byte mode sets only hold bytes"))
+                .collect();
+
+            self.offset += chunk.len();
+            return writer.write_all(&bytes).map_err(Error::Io);
+        }
+
+        // Put the start of a character cut off by the last chunk back in
+        // front of this one
+        let input: Cow<[u8]> = match self.pending.is_empty() {
+            true => Cow::Borrowed(chunk),
+            false => {
+                let mut input = std::mem::take(&mut self.pending);
+                input.extend_from_slice(chunk);
+                Cow::Owned(input)
+            }
+        };
+
+        let mut rest = &input[..];
+        loop {
+            let (valid, error) = match std::str::from_utf8(rest) {
+                Ok(text) => (text, None),
+                Err(e) => {
+                    let valid = std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default();
+                    (valid, Some(e))
+                }
+            };
+
+            let mut output = String::new();
+            translator.process_text(valid, self, &mut output);
+            writer.write_all(output.as_bytes())?;
+
+            let Some(e) = error else {
+                break;
+            };
+
+            // A sequence that runs off the end of the chunk may be finished by
+            // the next one
+            let invalid = &rest[e.valid_up_to()..];
+            let len = match e.error_len() {
+                Some(len) => len,
+                None => {
+                    self.pending = invalid.to_vec();
+                    break;
+                }
+            };
+
+            let offset = self.offset + input.len() - invalid.len();
+            self.write_invalid(translator, &invalid[..len], offset, &mut writer)?;
+            rest = &invalid[len..];
+        }
+
+        self.offset += input.len() - self.pending.len();
+        Ok(())
+    }
+
+    /// Handle the end of the input, where a character may have been cut off
+    pub(crate) fn finish(
+        &mut self,
+        translator: &Translator,
+        mut writer: impl Write,
+    ) -> Result<(), Error> {
+        let mut output = String::new();
+        translator.end_chars(self, &mut output);
+        writer.write_all(output.as_bytes())?;
+
+        let counts = &self.counts;
+        debug!(
+            "Read {} characters: {} transliterated, {} translated, {} deleted, {} squeezed",
+            counts.read, counts.transliterated, counts.translated, counts.deleted, counts.squeezed
+        );
+
+        let pending = std::mem::take(&mut self.pending);
+        match pending.is_empty() {
+            true => Ok(()),
+            false => self.write_invalid(translator, &pending, self.offset, writer),
+        }
+    }
+
+    /// Reject a sequence of bytes that is not UTF-8, or copy it to the output
+    /// unchanged. It is not a character, so it ends any run being squeezed.
+    fn write_invalid(
+        &mut self,
+        translator: &Translator,
+        bytes: &[u8],
+        offset: usize,
+        mut writer: impl Write,
+    ) -> Result<(), Error> {
+        if translator.invalid_utf8 == InvalidUtf8::Reject {
+            return Err(Error::InvalidUtf8 { offset });
+        }
+
+        // The bytes are not letters, so they end the word of any sigma before them
+        let mut output = String::new();
+        translator.end_chars(self, &mut output);
+        writer.write_all(output.as_bytes())?;
+
+        self.last = None;
+        self.cluster = None;
+        self.cased = false;
+        self.orphaned = false;
+        writer.write_all(bytes).map_err(Error::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // ************************************************************************
+    // builder tests
+    // ************************************************************************
+
+    #[test]
+    fn can_build_a_translator() {
+        let translator = Translator::builder("a-z").string2("A-Z").build();
+
+        assert!(translator.is_ok());
+    }
+
+    #[test]
+    fn translating_without_string2_returns_error() {
+        let result = Translator::builder("a-z").build();
+
+        assert!(matches!(result, Err(Error::MissingString2)));
+    }
+
+    #[test]
+    fn deleting_and_squeezing_without_string2_returns_error() {
+        let result = Translator::builder("a-z")
+            .delete(true)
+            .squeeze(true)
+            .build();
+
+        assert!(matches!(result, Err(Error::MissingString2)));
+    }
+
+    #[test]
+    fn deleting_with_string2_returns_error() {
+        let result = Translator::builder("a-z")
+            .string2("A-Z")
+            .delete(true)
+            .build();
+
+        assert!(matches!(result, Err(Error::IncompatibleFlags)));
+    }
+
+    #[test]
+    fn invalid_range_returns_error() {
+        let result = Translator::builder("z-a").string2("A-Z").build();
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidRange {
+                operand: Operand::String1,
+                offset: 0,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn empty_string2_returns_error() {
+        let result = Translator::builder("a-z").string2("").build();
+
+        assert!(matches!(result, Err(Error::EmptyString2)));
+    }
+
+    // ************************************************************************
+    // apply tests
+    // ************************************************************************
+
+    #[test]
+    fn can_apply_to_a_string() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+
+        let output = translator.apply("coding challenge");
+
+        assert!(matches!(output, Cow::Owned(_)));
+        assert_eq!(output, "CODING CHALLENGE");
+    }
+
+    #[test]
+    fn unchanged_string_is_borrowed() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+
+        let output = translator.apply("123");
+
+        assert!(matches!(output, Cow::Borrowed("123")));
+    }
+
+    #[test]
+    fn can_delete_and_squeeze_a_string() {
+        let translator = Translator::builder("0-9")
+            .string2("l")
+            .delete(true)
+            .squeeze(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("chal1l2lenge"), "chalenge");
+    }
+
+    #[test]
+    fn can_apply_to_bytes() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+
+        let output = translator.apply_bytes(b"coding\nchallenge\n").unwrap();
+
+        assert_eq!(output.as_ref(), b"CODING\nCHALLENGE\n");
+    }
+
+    #[test]
+    fn applying_to_invalid_utf8_returns_error() {
+        let translator = Translator::builder("a-z").string2("A-Z").build().unwrap();
+
+        let result = translator.apply_bytes(b"ab\xffc");
+
+        assert!(matches!(result, Err(Error::InvalidUtf8 { offset: 2 })));
+    }
+
+    #[test]
+    fn can_apply_to_bytes_in_byte_mode() {
+        let translator = Translator::builder("\\351")
+            .string2("e")
+            .bytes(true)
+            .build()
+            .unwrap();
+
+        let output = translator.apply_bytes(b"caf\xe9").unwrap();
+
+        assert_eq!(output.as_ref(), b"cafe");
+    }
+
+    #[test]
+    fn applying_in_byte_mode_matches_the_bytes_of_characters() {
+        let translator = Translator::builder("é")
+            .delete(true)
+            .bytes(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("café"), "caf");
+    }
+
+    #[test]
+    fn applying_in_byte_mode_replaces_broken_characters() {
+        let translator = Translator::builder("\\251")
+            .delete(true)
+            .bytes(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("café"), "caf\u{fffd}");
+    }
+
+    // ************************************************************************
+    // process tests
+    // ************************************************************************
+
+    #[test]
+    fn can_process_a_reader_into_a_writer() {
+        let translator = Translator::builder("\\n").string2(" ").build().unwrap();
+        let mut writer = Vec::new();
+
+        let result = translator.process(Cursor::new("coding\nchallenge\n"), &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"coding challenge ");
+    }
+
+    #[test]
+    fn can_process_input_larger_than_a_chunk() {
+        let translator = Translator::builder("a").squeeze(true).build().unwrap();
+        let input = format!("b{}b", "a".repeat(CHUNK_SIZE * 3));
+        let mut writer = Vec::new();
+
+        let result = translator.process(Cursor::new(input), &mut writer);
+
+        assert!(result.is_ok());
+        assert_eq!(writer, b"bab");
+    }
+
+    // ************************************************************************
+    // case mapping tests
+    // ************************************************************************
+
+    #[test]
+    fn final_sigma_waits_for_the_next_chunk() {
+        let translator = Translator::builder("[:upper:]")
+            .string2("[:lower:]")
+            .build()
+            .unwrap();
+        let mut stream = Stream::default();
+        let mut output = Vec::new();
+
+        stream
+            .write(&translator, "ΟΔΟΣ".as_bytes(), &mut output)
+            .unwrap();
+        stream
+            .write(&translator, " ΑΣ".as_bytes(), &mut output)
+            .unwrap();
+        stream
+            .write(&translator, "Α".as_bytes(), &mut output)
+            .unwrap();
+        stream.finish(&translator, &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "οδος ασα");
+    }
+
+    #[test]
+    fn can_use_simple_case_mappings() {
+        let translator = Translator::builder("[:lower:]")
+            .string2("[:upper:]")
+            .case_mapping(CaseMapping::Simple)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("straße"), "STRAßE");
+    }
+
+    #[test]
+    fn can_use_turkish_case_rules() {
+        let translator = Translator::builder("[:lower:]")
+            .string2("[:upper:]")
+            .case_rules(CaseRules::Turkic)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("izmir"), "İZMİR");
+    }
+
+    #[test]
+    fn case_rules_come_from_the_language_of_a_locale() {
+        assert_eq!(CaseRules::from_locale("tr_TR.UTF-8"), CaseRules::Turkic);
+        assert_eq!(CaseRules::from_locale("az-Latn"), CaseRules::Turkic);
+        assert_eq!(CaseRules::from_locale("lt"), CaseRules::Lithuanian);
+        assert_eq!(CaseRules::from_locale("en_US.UTF-8"), CaseRules::Default);
+        assert_eq!(CaseRules::from_locale("C"), CaseRules::Ascii);
+        assert_eq!(CaseRules::from_locale("POSIX"), CaseRules::Ascii);
+        assert_eq!(CaseRules::from_locale("C.UTF-8"), CaseRules::Default);
+        assert_eq!(CaseRules::from_locale(""), CaseRules::Default);
+    }
+
+    // ************************************************************************
+    // grapheme mode tests
+    // ************************************************************************
+
+    #[test]
+    fn can_delete_clusters_with_a_translator() {
+        let translator = Translator::builder("👨‍👩‍👧")
+            .delete(true)
+            .graphemes(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("a👨‍👩‍👧b👨"), "ab👨");
+    }
+
+    #[test]
+    fn graphemes_have_no_effect_in_byte_mode() {
+        let translator = Translator::builder("e\\u{301}")
+            .delete(true)
+            .graphemes(true)
+            .bytes(true)
+            .build();
+
+        assert!(matches!(translator, Err(Error::NotAByte { .. })));
+    }
+
+    #[test]
+    fn cluster_counts_as_one_character() {
+        let translator = Translator::builder("e\u{301}")
+            .string2("x")
+            .graphemes(true)
+            .build()
+            .unwrap();
+        let mut stream = Stream::default();
+        let mut output = Vec::new();
+
+        stream
+            .write(&translator, "e\u{301}e".as_bytes(), &mut output)
+            .unwrap();
+        stream.finish(&translator, &mut output).unwrap();
+
+        assert_eq!(output, b"xe");
+        assert_eq!(
+            stream.counts,
+            Counts {
+                read: 2,
+                transliterated: 0,
+                translated: 1,
+                deleted: 0,
+                squeezed: 0
+            }
+        );
+    }
+
+    #[test]
+    fn line_is_written_without_waiting_for_the_next_chunk() {
+        let translator = Translator::builder("a")
+            .string2("b")
+            .graphemes(true)
+            .normalize(Some(Normalization::Nfc))
+            .build()
+            .unwrap();
+        let mut stream = Stream::default();
+        let mut output = Vec::new();
+
+        stream.write(&translator, b"abc\n", &mut output).unwrap();
+
+        assert_eq!(output, b"bbc\n");
+    }
+
+    // ************************************************************************
+    // normalization tests
+    // ************************************************************************
+
+    #[test]
+    fn can_normalize_with_a_translator() {
+        let translator = Translator::builder("é")
+            .string2("E")
+            .normalize(Some(Normalization::Nfc))
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("cafe\u{301}"), "cafE");
+    }
+
+    #[test]
+    fn normalization_has_no_effect_in_byte_mode() {
+        let translator = Translator::builder("e")
+            .string2("a")
+            .normalize_output(Some(Normalization::Nfc))
+            .bytes(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            translator.apply_bytes(b"e\xcc\x81").unwrap().as_ref(),
+            b"a\xcc\x81"
+        );
+    }
+
+    // ************************************************************************
+    // transliteration tests
+    // ************************************************************************
+
+    #[test]
+    fn can_transliterate_with_a_translator() {
+        let translator = Translator::builder("a-z")
+            .string2("A-Z")
+            .ascii(true)
+            .ascii_fallback(AsciiFallback::Keep)
+            .build()
+            .unwrap();
+
+        assert_eq!(translator.apply("smørrebrød 中"), "SMORREBROD 中");
+    }
+
+    #[test]
+    fn a_translator_can_only_transliterate() {
+        let translator = Translator::builder("[:rune:]").ascii(true).build().unwrap();
+
+        assert_eq!(translator.apply("Œuvre"), "OEuvre");
+    }
+
+    // ************************************************************************
+    // count tests
+    // ************************************************************************
+
+    #[test]
+    fn stream_counts_what_happens_to_characters() {
+        let translator = Translator::builder("a-z")
+            .string2("A-Z")
+            .squeeze(true)
+            .build()
+            .unwrap();
+        let mut stream = Stream::default();
+        let mut output = Vec::new();
+
+        stream.write(&translator, b"Hello  ", &mut output).unwrap();
+        stream.write(&translator, b"world", &mut output).unwrap();
+        stream.finish(&translator, &mut output).unwrap();
+
+        assert_eq!(output, b"HELO  WORLD");
+        assert_eq!(
+            stream.counts,
+            Counts {
+                read: 12,
+                transliterated: 0,
+                translated: 9,
+                deleted: 0,
+                squeezed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn stream_counts_deleted_characters() {
+        let translator = Translator::builder("0-9").delete(true).build().unwrap();
+        let mut stream = Stream::default();
+        let mut output = Vec::new();
+
+        stream.write(&translator, b"c0d1ng", &mut output).unwrap();
+
+        assert_eq!(stream.counts.deleted, 2);
+        assert_eq!(stream.counts.translated, 0);
+    }
+}
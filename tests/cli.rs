@@ -2,7 +2,22 @@
 /// with the required combination of arguments.
 use anyhow::Result;
 use assert_cmd::prelude::*;
+use std::io::{BufRead, BufReader, Write};
 use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// The built binary, with the locale variables cleared so that the tests do not
+/// depend on the locale of the machine running them
+fn cctr() -> Result<Command> {
+    let mut cmd = Command::cargo_bin("cctr")?;
+    cmd.env_remove("LC_ALL")
+        .env_remove("LC_CTYPE")
+        .env_remove("LANG");
+
+    Ok(cmd)
+}
 
 // ************************************************************************
 // translate mode tests
@@ -12,7 +27,7 @@ use std::process::{Command, Stdio};
 fn can_run_in_translate_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c
     cmd.arg("c").arg("C");
@@ -27,7 +42,7 @@ fn can_run_in_translate_mode() -> Result<()> {
 fn can_use_the_capital_c_flag_in_translate_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add two args and the s flag
     cmd.arg("-C").arg("c").arg("C");
@@ -42,7 +57,7 @@ fn can_use_the_capital_c_flag_in_translate_mode() -> Result<()> {
 fn can_use_the_c_flag_in_translate_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add two args and the s flag
     cmd.arg("-c").arg("c").arg("C");
@@ -57,7 +72,7 @@ fn can_use_the_c_flag_in_translate_mode() -> Result<()> {
 fn can_use_the_s_flag_in_translate_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add two args and the s flag
     cmd.arg("-s").arg("c").arg("C");
@@ -72,7 +87,7 @@ fn can_use_the_s_flag_in_translate_mode() -> Result<()> {
 fn can_use_the_u_flag_in_translate_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add two args and the s flag
     cmd.arg("-u").arg("c").arg("C");
@@ -87,7 +102,7 @@ fn can_use_the_u_flag_in_translate_mode() -> Result<()> {
 fn can_use_the_ccsu_flags_in_translate_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add two args and the s flag
     cmd.arg("-Ccsu").arg("c").arg("C");
@@ -98,6 +113,34 @@ fn can_use_the_ccsu_flags_in_translate_mode() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn can_translate_and_squeeze() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("aabbcc xyz  q")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-s")
+        .arg("a-c")
+        .arg("X")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"X xyz  q\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
 // ************************************************************************
 // delete mode tests
 // ************************************************************************
@@ -106,7 +149,7 @@ fn can_use_the_ccsu_flags_in_translate_mode() -> Result<()> {
 fn can_run_in_delete_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c plus the d flag
     cmd.arg("-d").arg("c");
@@ -121,7 +164,7 @@ fn can_run_in_delete_mode() -> Result<()> {
 fn can_run_in_delete_mode_with_ccu_flags() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c plus the d flag
     cmd.arg("-Ccud").arg("c");
@@ -136,7 +179,7 @@ fn can_run_in_delete_mode_with_ccu_flags() -> Result<()> {
 fn two_string_with_just_the_delete_flag_return_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add two args and the d flag
     cmd.arg("-d").arg("c").arg("C");
@@ -151,7 +194,7 @@ fn two_string_with_just_the_delete_flag_return_error() -> Result<()> {
 fn one_string_and_no_d_or_s_flag_returns_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c
     cmd.arg("c");
@@ -166,7 +209,7 @@ fn one_string_and_no_d_or_s_flag_returns_error() -> Result<()> {
 fn one_string_and_flag_that_is_not_d_or_s_returns_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c and the second arg C
     cmd.arg("-C").arg("c");
@@ -181,7 +224,7 @@ fn one_string_and_flag_that_is_not_d_or_s_returns_error() -> Result<()> {
 fn adding_all_flags_and_only_one_string_returns_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c plus all five flags
     cmd.arg("-Ccsud").arg("c");
@@ -200,7 +243,7 @@ fn adding_all_flags_and_only_one_string_returns_error() -> Result<()> {
 fn can_run_in_compress_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c plus the s flag
     cmd.arg("-s").arg("c");
@@ -219,7 +262,7 @@ fn can_run_in_compress_mode() -> Result<()> {
 fn can_run_in_delete_and_compress_mode() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c plus all five flags
     cmd.arg("-Ccsud").arg("c").arg("C");
@@ -238,7 +281,7 @@ fn can_run_in_delete_and_compress_mode() -> Result<()> {
 fn running_with_three_string_args_returns_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c
     cmd.arg("c").arg("C").arg("a");
@@ -253,7 +296,7 @@ fn running_with_three_string_args_returns_error() -> Result<()> {
 fn running_with_unknown_flag_returns_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the arg c
     cmd.arg("-a").arg("C");
@@ -268,7 +311,7 @@ fn running_with_unknown_flag_returns_error() -> Result<()> {
 fn running_with_an_empty_string_returns_error() -> Result<()> {
     // load the main function in the binary file and run the
     // built version of it
-    let mut cmd = Command::cargo_bin("cctr")?;
+    let mut cmd = cctr()?;
 
     // add the empty string arg
     cmd.arg("");
@@ -295,7 +338,7 @@ fn can_delete_single_characters() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("C")
@@ -322,7 +365,7 @@ fn can_delete_many_characters() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("Cdg")
@@ -349,7 +392,7 @@ fn can_delete_everything() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("Coding challenge")
@@ -376,7 +419,7 @@ fn can_delete_digits() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("123")
@@ -403,7 +446,7 @@ fn can_delete_whitespace() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg(" ")
@@ -430,7 +473,7 @@ fn can_delete_special_chars() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("@")
@@ -457,7 +500,7 @@ fn can_delete_upper_class() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:upper:]")
@@ -484,7 +527,7 @@ fn can_delete_lower_class() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:lower:]")
@@ -511,7 +554,7 @@ fn can_delete_alphabetic_chars() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:alpha:]")
@@ -538,7 +581,7 @@ fn can_delete_alphanumeric_chars() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:alnum:]")
@@ -565,7 +608,7 @@ fn can_delete_blanks() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:blank:]")
@@ -592,7 +635,7 @@ fn can_delete_control_characters() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:cntrl:]")
@@ -602,7 +645,8 @@ fn can_delete_control_characters() -> Result<()> {
         .wait_with_output()
         .expect("Failed to wait on cctr");
 
-    assert_eq!(b"123challenge\n", output.stdout.as_slice());
+    // the newline from echo is a control character too
+    assert_eq!(b"123challenge", output.stdout.as_slice());
 
     Ok(())
 }
@@ -619,7 +663,7 @@ fn can_delete_digit_characters() -> Result<()> {
         .expect("Failed to open echo stdout");
 
     // run the cctr cli tool
-    let output = Command::cargo_bin("cctr")?
+    let output = cctr()?
         .stdin(Stdio::from(echo_out))
         .arg("-d")
         .arg("[:digit:]")
@@ -633,3 +677,1385 @@ fn can_delete_digit_characters() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn can_delete_punctuation_class() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Coding, challenge!")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-d")
+        .arg("[:punct:]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"Coding challenge\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_delete_hex_digit_class() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("0xFF coding")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-d")
+        .arg("[:xdigit:]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"x oing\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_delete_unprintable_characters() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Coding\u{1} challenge")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-cd")
+        .arg("[:print:]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // the newline from echo is not printable either
+    assert_eq!(b"Coding challenge", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_keep_only_cjk_characters() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("漢字とカタカナ abc")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-cd")
+        .arg("[:ideogram:][:phonogram:]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // the newline from echo is not a CJK character either
+    assert_eq!("漢字とカタカナ".as_bytes(), output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_delete_an_equivalence_class() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("élève été")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-d")
+        .arg("[=e=]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"lv t\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_translate_an_equivalence_class() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Renée")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("[=e=]")
+        .arg("e")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"Renee\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_delete_combining_marks_with_their_character() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("Rene\\xcc\\x81e\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool to delete every e along with its accents
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("--delete-marks")
+        .arg("-d")
+        .arg("e")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"Rn\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// range tests
+// ************************************************************************
+
+#[test]
+fn can_translate_ranges() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("coding challenge")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"CODING CHALLENGE\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_translate_rot13() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Hello, World")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("a-zA-Z")
+        .arg("n-za-mN-ZA-M")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"Uryyb, Jbeyq\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn translations_do_not_chain() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("abc")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("ab")
+        .arg("bc")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"bcc\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn reversed_range_returns_error() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("coding challenge")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool with a range that runs backwards
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-d")
+        .arg("z-a")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    Ok(())
+}
+
+// ************************************************************************
+// escape sequence tests
+// ************************************************************************
+
+#[test]
+fn can_delete_escaped_tabs() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("printf")
+        .arg("coding\\tchallenge\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-d")
+        .arg("\\t")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"codingchallenge\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// complement flag tests
+// ************************************************************************
+
+#[test]
+fn can_delete_the_complement_with_the_c_flag() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Coding, challenge #1!")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-dc")
+        .arg("[:alnum:]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // the newline from echo is not in string1 either
+    assert_eq!(b"Codingchallenge1", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_delete_the_complement_with_the_capital_c_flag() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Coding, challenge #1!")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-Cd")
+        .arg("a-z")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // the newline from echo is not in string1 either
+    assert_eq!(b"odingchallenge", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_translate_the_complement_of_a_set() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("coding challenge 2024")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-c")
+        .arg("a-z")
+        .arg("_")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // the newline from echo is not in string1 either
+    assert_eq!(b"coding_challenge______", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn capital_c_flag_pairs_the_complement_in_code_point_order() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Aa")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool on a complement that only holds `A` and `a`
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-C")
+        .arg("\\0-@B-`b-\\u{10ffff}")
+        .arg("xy")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // `A` comes before `a` in code point order
+    assert_eq!(b"xy\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn c_flag_pairs_the_complement_in_collation_sequence() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Aa")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool on a complement that only holds `A` and `a`
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-c")
+        .arg("\\0-@B-`b-\\u{10ffff}")
+        .arg("xy")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // lowercase comes before uppercase in collation sequence
+    assert_eq!(b"yx\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_squeeze_the_complement_of_a_set() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Coddding   challenge!!")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-sC")
+        .arg("[:alpha:]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"Coddding challenge!\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_delete_the_complement_and_squeeze() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Coding   challenge 2024")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("-dsc")
+        .arg("a-z ")
+        .arg(" l")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    // the newline from echo is not in string1 either
+    assert_eq!(b"oding chalenge ", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// repeat tests
+// ************************************************************************
+
+#[test]
+fn can_translate_with_a_repeat() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("coding challenge")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("a-z")
+        .arg("[x*]")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"xxxxxx xxxxxxxxx\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_translate_with_a_counted_repeat() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("coding challenge")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("a-f")
+        .arg("[_*5]A")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"_o_ing _h_ll_ng_\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// byte mode tests
+// ************************************************************************
+
+#[test]
+fn can_translate_latin1_input_with_the_bytes_flag() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("caf\\351\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("--bytes")
+        .arg("\\351")
+        .arg("e")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"cafe\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn c_locale_turns_on_byte_mode() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("caf\\351\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool in the C locale
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .env("LC_ALL", "C")
+        .arg("\\351")
+        .arg("e")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"cafe\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn invalid_utf8_returns_error() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("ab\\377c\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool on input that is not UTF-8
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Unable to translate stdin: Invalid UTF-8 at byte offset 2.\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn c_locale_reads_the_sets_as_utf8_bytes() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("caf\\xc3\\xa9 5\\xe2\\x82\\xac\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool in the C locale, where é and € are several bytes
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .env("LC_ALL", "C")
+        .arg("-d")
+        .arg("é€")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert!(output.status.success());
+    assert_eq!(b"caf 5\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_pass_invalid_utf8_through() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("ab\\377c\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("--invalid-utf8")
+        .arg("pass")
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"AB\xffC\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// newline tests
+// ************************************************************************
+
+#[test]
+fn can_join_lines() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("coding\\nchallenge\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("-d")
+        .arg("\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"codingchallenge", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_translate_newlines_into_spaces() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("coding\\nchallenge\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("\\n")
+        .arg(" ")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"coding challenge ", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn can_collapse_blank_lines() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("coding\\n\\n\\nchallenge\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("-s")
+        .arg("\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"coding\nchallenge\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn missing_trailing_newline_is_not_added() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("coding challenge")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"CODING CHALLENGE", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn crlf_line_endings_are_kept() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("coding\\r\\nchallenge\\r\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"CODING\r\nCHALLENGE\r\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// unbuffered flag tests
+// ************************************************************************
+
+#[test]
+fn unbuffered_output_arrives_before_stdin_closes() -> Result<()> {
+    // run the cctr cli tool and keep its stdin open
+    let mut child = cctr()?
+        .arg("-u")
+        .arg("c")
+        .arg("C")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process");
+
+    let mut stdin = child.stdin.take().expect("Failed to open cctr stdin");
+    let stdout = child.stdout.take().expect("Failed to open cctr stdout");
+
+    // read the output on another thread so the test can time out
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if sender.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+
+    // each line should be written out while stdin is still open
+    stdin
+        .write_all(b"coding challenge\n")
+        .expect("Failed to write to cctr stdin");
+    stdin.flush().expect("Failed to flush cctr stdin");
+    let line = receiver
+        .recv_timeout(Duration::from_secs(10))
+        .expect("Output was buffered");
+    assert_eq!(line, "Coding Challenge");
+
+    stdin
+        .write_all(b"come as you are\n")
+        .expect("Failed to write to cctr stdin");
+    stdin.flush().expect("Failed to flush cctr stdin");
+    let line = receiver
+        .recv_timeout(Duration::from_secs(10))
+        .expect("Output was buffered");
+    assert_eq!(line, "Come as you are");
+
+    // close stdin and let the process finish
+    drop(stdin);
+    let status = child.wait().expect("Failed to wait on cctr");
+    assert!(status.success());
+
+    Ok(())
+}
+
+#[test]
+fn buffered_output_contains_every_line() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("coding challenge\\ncome as you are\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("c")
+        .arg("C")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(
+        b"Coding Challenge\nCome as you are\n",
+        output.stdout.as_slice()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn can_use_full_and_simple_case_mappings() -> Result<()> {
+    for (flag, expected) in [("full", "STRASSE\n"), ("simple", "STRAßE\n")] {
+        // mock an echo command that then gets piped to the cctr stdin
+        let echo_out = Command::new("echo")
+            .arg("straße")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start echo process")
+            .stdout
+            .expect("Failed to open echo stdout");
+
+        // run the cctr cli tool
+        let output = cctr()?
+            .stdin(Stdio::from(echo_out))
+            .arg("--case-mapping")
+            .arg(flag)
+            .arg("[:lower:]")
+            .arg("[:upper:]")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start cctr process")
+            .wait_with_output()
+            .expect("Failed to wait on cctr");
+
+        assert_eq!(expected.as_bytes(), output.stdout.as_slice());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn case_rules_follow_the_locale() -> Result<()> {
+    for (env, flag) in [("tr_TR.UTF-8", None), ("en_US.UTF-8", Some("tr"))] {
+        // mock an echo command that then gets piped to the cctr stdin
+        let echo_out = Command::new("echo")
+            .arg("DIYARBAKIR")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start echo process")
+            .stdout
+            .expect("Failed to open echo stdout");
+
+        // run the cctr cli tool with Turkish case rules from the environment
+        // or from the flag
+        let mut cmd = cctr()?;
+        cmd.env("LC_CTYPE", env);
+        if let Some(locale) = flag {
+            cmd.arg("--locale").arg(locale);
+        }
+        let output = cmd
+            .stdin(Stdio::from(echo_out))
+            .arg("[:upper:]")
+            .arg("[:lower:]")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start cctr process")
+            .wait_with_output()
+            .expect("Failed to wait on cctr");
+
+        assert_eq!("dıyarbakır\n".as_bytes(), output.stdout.as_slice());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn locale_flag_in_byte_mode_returns_error() -> Result<()> {
+    for (env, flag) in [("en_US.UTF-8", Some("--bytes")), ("C", None)] {
+        // run the cctr cli tool with Turkish case rules in byte mode, which
+        // is either asked for or comes from the C locale
+        let mut cmd = cctr()?;
+        cmd.env("LC_CTYPE", env);
+        if let Some(flag) = flag {
+            cmd.arg(flag);
+        }
+        cmd.arg("--locale")
+            .arg("tr_TR")
+            .arg("[:upper:]")
+            .arg("[:lower:]");
+
+        // make sure the function is a failure and says why
+        cmd.assert()
+            .failure()
+            .code(1)
+            .stderr(predicates::str::contains(
+                "The locale `tr_TR` cannot be used in byte mode",
+            ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn graphemes_flag_deletes_whole_clusters() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("cafe\\xcc\\x81 cafe\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool to delete an e with a combining acute accent
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("--graphemes")
+        .arg("-d")
+        .arg("e\\u{301}")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"caf cafe\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn normalize_flag_matches_decomposed_accents() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("cafe\\xcc\\x81 caf\\xc3\\xa9\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool on input with a decomposed and a precomposed é
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("--normalize")
+        .arg("nfc")
+        .arg("é")
+        .arg("e")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"cafe cafe\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// ascii flag tests
+// ************************************************************************
+
+#[test]
+fn ascii_flag_transliterates_the_input_on_its_own() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Ærøskøbing – 5€")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool without string1
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("--ascii")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"AEroskobing - 5EUR\n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn ascii_flag_still_needs_string1_to_delete() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("--ascii").arg("-d");
+
+    // string1 is only optional when transliterating on its own
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicates::str::contains("<STRING1>"));
+
+    Ok(())
+}
+
+#[test]
+fn ascii_fallback_flag_drops_what_cannot_be_spelled() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("Tōkyō 東京")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool, squeezing the spaces left behind
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("--ascii")
+        .arg("--ascii-fallback")
+        .arg("drop")
+        .arg("-s")
+        .arg(" ")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"Tokyo \n", output.stdout.as_slice());
+
+    Ok(())
+}
+
+// ************************************************************************
+// error tests
+// ************************************************************************
+
+#[test]
+fn unknown_class_points_at_the_class() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("a[:alhpa:]").arg("x");
+
+    // the caret line sits under the class as it was written in string1
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::starts_with(
+            "Unknown class `[:alhpa:]` in string1. Did you mean `[:alpha:]`?\n  a[:alhpa:]\n   ^^^^^^^^^\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_range_in_string2_points_at_the_range() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("a-z").arg("A-Zz-a");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::contains("  A-Zz-a\n     ^^^\n"));
+
+    Ok(())
+}
+
+#[test]
+fn untranslatable_class_explains_what_string2_can_hold() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("[:alnum:]").arg("[:digit:]");
+
+    cmd.assert().failure().code(1).stderr(
+        "Unable to translate [:alnum:] into [:digit:]. Only [:lower:] and [:upper:] can \
+         change the characters they are paired with.\n",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn missing_string2_prints_usage() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("c");
+
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicates::str::starts_with(
+            "These options need string2.\nusage:",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn unknown_class_lists_the_supported_classes() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("[:uper:]").arg("x");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::contains("Did you mean `[:upper:]`?"))
+        .stderr(predicates::str::contains(
+            "Supported classes: [:alnum:] [:alpha:]",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn unterminated_equivalence_class_suggests_a_fix() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("[=e]").arg("x");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::starts_with(
+            "Unterminated `[=e]`. Did you mean `[=e=]`?",
+        ));
+
+    Ok(())
+}
+
+// ************************************************************************
+// verbosity tests
+// ************************************************************************
+
+#[test]
+fn debug_level_logs_the_mode_sets_and_counts() -> Result<()> {
+    // mock a printf command that then gets piped to the cctr stdin
+    let printf_out = Command::new("printf")
+        .arg("Hello  world\\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start printf process")
+        .stdout
+        .expect("Failed to open printf stdout");
+
+    // run the cctr cli tool at the debug level
+    let output = cctr()?
+        .stdin(Stdio::from(printf_out))
+        .arg("-vvv")
+        .arg("-s")
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(b"HELO  WORLD\n", output.stdout.as_slice());
+    assert!(stderr.contains("debug: Mode: TranslateCompress"));
+    assert!(stderr.contains("debug: string1 compiled to [a-z]"));
+    assert!(stderr.contains(
+        "debug: Read 13 characters: 0 transliterated, 9 translated, 0 deleted, 1 squeezed"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn nothing_is_logged_by_default() -> Result<()> {
+    // mock an echo command that then gets piped to the cctr stdin
+    let echo_out = Command::new("echo")
+        .arg("coding")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to start echo process")
+        .stdout
+        .expect("Failed to open echo stdout");
+
+    // run the cctr cli tool
+    let output = cctr()?
+        .stdin(Stdio::from(echo_out))
+        .arg("a-z")
+        .arg("A-Z")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start cctr process")
+        .wait_with_output()
+        .expect("Failed to wait on cctr");
+
+    assert_eq!(b"CODING\n", output.stdout.as_slice());
+    assert!(output.stderr.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn errors_are_shown_when_quiet() -> Result<()> {
+    let mut cmd = cctr()?;
+
+    cmd.arg("-q").arg("c");
+
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicates::str::contains("These options need string2."));
+
+    Ok(())
+}